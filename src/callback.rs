@@ -0,0 +1,70 @@
+use alloc::boxed::Box;
+use core::fmt::Debug;
+
+use crate::WeakInnerRef;
+
+/// A callback registered with [`KillSwitch::on_kill()`](crate::KillSwitch::on_kill)
+pub(crate) struct Callback(pub(crate) Box<dyn FnOnce() + Send>);
+
+impl Callback {
+    /// Run the callback, catching any panic so that it cannot escape into the killing thread. The
+    /// panic hook has already reported the panic by the time it is caught.
+    #[cfg(feature = "std")]
+    pub(crate) fn call(self) {
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(self.0));
+    }
+
+    /// Run the callback. Without `std` there is no way to catch a panic, but such targets usually
+    /// abort on panic anyway.
+    #[cfg(not(feature = "std"))]
+    pub(crate) fn call(self) {
+        (self.0)()
+    }
+}
+
+impl Debug for Callback {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Callback")
+    }
+}
+
+/// Handle for a callback registered with [`KillSwitch::on_kill()`](crate::KillSwitch::on_kill).
+/// Dropping the handle unregisters the callback, so it must be held for as long as the callback
+/// should remain registered.
+#[derive(Debug)]
+#[must_use = "dropping a CallbackHandle unregisters the callback"]
+pub struct CallbackHandle {
+    registration: Option<(WeakInnerRef, u64)>,
+}
+
+impl CallbackHandle {
+    pub(crate) fn new(inner: WeakInnerRef, id: u64) -> Self {
+        Self {
+            registration: Some((inner, id)),
+        }
+    }
+
+    /// Handle for a callback which has already been run
+    pub(crate) fn inert() -> Self {
+        Self { registration: None }
+    }
+
+    /// Leave the callback registered for the rest of the kill switch's life, rather than
+    /// unregistering it when this handle goes out of scope
+    pub fn detach(mut self) {
+        self.registration = None;
+    }
+}
+
+impl Drop for CallbackHandle {
+    fn drop(&mut self) {
+        if let Some((inner, id)) = self.registration.take() {
+            if let Some(inner) = inner.upgrade() {
+                // Dropped only once the lock is released, as whatever the callback captured may
+                // itself need to lock this switch
+                let removed = inner.lock().callbacks.remove(&id);
+                drop(removed);
+            }
+        }
+    }
+}
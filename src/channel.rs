@@ -0,0 +1,240 @@
+use alloc::{boxed::Box, sync::Arc};
+use std::{
+    error::Error,
+    fmt::Display,
+    sync::mpsc::{self, Receiver, RecvTimeoutError, SendError, Sender, TryRecvError},
+    time::{Duration, Instant},
+};
+
+use crate::{CallbackHandle, KillSwitchWatcher};
+
+/// A plain channel cannot be woken from outside, so receiving blocks for at most this long at a
+/// time before checking the kill switch again. This bounds how long a kill can go unnoticed, at
+/// the cost of about 100 wakeups a second for every blocked receiver.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Error returned by [`KillSwitchWatcher::recv()`], [`KillSwitchWatcher::recv_timeout()`], and
+/// the same methods on [`KillableReceiver`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvOrKilled {
+    /// The kill switch was flipped before a message arrived
+    Killed,
+    /// The timeout elapsed before a message arrived
+    Timeout,
+    /// Every sender was dropped, so no more messages can arrive
+    Disconnected,
+}
+
+impl Error for RecvOrKilled {}
+
+impl Display for RecvOrKilled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecvOrKilled::Killed => write!(f, "kill switch was flipped"),
+            RecvOrKilled::Timeout => write!(f, "timed out waiting on channel"),
+            RecvOrKilled::Disconnected => write!(f, "channel is empty and disconnected"),
+        }
+    }
+}
+
+/// Iterator returned by [`KillSwitchWatcher::recv_iter()`], which yields messages until the kill
+/// switch is flipped or the channel is disconnected.
+///
+/// Like [`KillSwitchWatcher::recv()`], this polls while waiting, waking up about 100 times a
+/// second. Use [`KillSwitchWatcher::channel()`] instead to avoid that.
+#[derive(Debug)]
+pub struct RecvIter<'a, T> {
+    watcher: KillSwitchWatcher,
+    rx: &'a Receiver<T>,
+}
+
+impl<T> Iterator for RecvIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.watcher.recv(self.rx).ok()
+    }
+}
+
+impl KillSwitchWatcher {
+    /// Block until a message arrives on `rx`, returning early with [`RecvOrKilled::Killed`] if
+    /// the kill switch is flipped. Once the switch has been flipped this fails even if messages are
+    /// waiting, so that shutdown is not held up by a busy channel.
+    ///
+    /// The receiver cannot be woken by the kill switch directly, so it is polled: a kill is
+    /// noticed within a few milliseconds rather than immediately, and every blocked call wakes up
+    /// about 100 times a second. Where the channel can be created up front,
+    /// [`channel()`](Self::channel) avoids both
+    pub fn recv<T>(&self, rx: &Receiver<T>) -> Result<T, RecvOrKilled> {
+        self.recv_until(rx, None)
+    }
+
+    /// Block until a message arrives on `rx`, returning early with [`RecvOrKilled::Killed`] if
+    /// the kill switch is flipped, or [`RecvOrKilled::Timeout`] once `timeout` has elapsed. See
+    /// [`recv()`](Self::recv)
+    pub fn recv_timeout<T>(&self, rx: &Receiver<T>, timeout: Duration) -> Result<T, RecvOrKilled> {
+        self.recv_until(rx, Instant::now().checked_add(timeout))
+    }
+
+    /// Iterate over messages from `rx`, blocking for each one as with [`recv()`](Self::recv).
+    /// Iteration ends once the kill switch is flipped or every sender has been dropped.
+    pub fn recv_iter<'a, T>(&self, rx: &'a Receiver<T>) -> RecvIter<'a, T> {
+        RecvIter {
+            watcher: self.clone(),
+            rx,
+        }
+    }
+
+    /// Create a channel whose receiver wakes up as soon as the kill switch is flipped, without
+    /// polling. On kill the switch sends a wake-up message of its own down the channel, so unlike
+    /// [`recv()`](Self::recv) on a plain [`Receiver`], a blocked receiver costs nothing while it
+    /// waits.
+    ///
+    /// ```
+    /// use killswitch_std::{KillSwitch, RecvOrKilled};
+    /// use std::thread;
+    ///
+    /// let kill = KillSwitch::default();
+    /// let (tx, rx) = kill.watcher().channel();
+    ///
+    /// let consumer = thread::spawn(move || rx.iter().sum::<u32>());
+    /// for n in 1..=10 {
+    ///     tx.send(n).unwrap();
+    /// }
+    /// kill.kill().unwrap();
+    /// assert!(consumer.join().unwrap() <= 55);
+    /// ```
+    pub fn channel<T: Send + 'static>(&self) -> (KillableSender<T>, KillableReceiver<T>) {
+        let (tx, rx) = mpsc::channel();
+        // The callback only holds a weak reference, so that dropping every `KillableSender`
+        // still disconnects the channel
+        let tx = Arc::new(tx);
+        let wake = Arc::downgrade(&tx);
+        let on_kill = self.inner.on_kill(Box::new(move || {
+            if let Some(tx) = wake.upgrade() {
+                let _ = tx.send(None);
+            }
+        }));
+        (
+            KillableSender { tx },
+            KillableReceiver {
+                rx,
+                watcher: self.clone(),
+                _on_kill: on_kill,
+            },
+        )
+    }
+
+    fn recv_until<T>(
+        &self,
+        rx: &Receiver<T>,
+        deadline: Option<Instant>,
+    ) -> Result<T, RecvOrKilled> {
+        loop {
+            if !self.is_alive() {
+                return Err(RecvOrKilled::Killed);
+            }
+            let mut slice = POLL_INTERVAL;
+            if let Some(deadline) = deadline {
+                let now = Instant::now();
+                if now >= deadline {
+                    // Like `Receiver::recv_timeout()`, a message which is already waiting is
+                    // returned even once the timeout has elapsed
+                    return match rx.try_recv() {
+                        Ok(message) => Ok(message),
+                        Err(TryRecvError::Empty) => Err(RecvOrKilled::Timeout),
+                        Err(TryRecvError::Disconnected) => Err(RecvOrKilled::Disconnected),
+                    };
+                }
+                slice = slice.min(deadline - now);
+            }
+            match rx.recv_timeout(slice) {
+                Ok(message) => return Ok(message),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return Err(RecvOrKilled::Disconnected),
+            }
+        }
+    }
+}
+
+/// Sending half of a channel created by [`KillSwitchWatcher::channel()`]. Clones send to the same
+/// channel, and the channel is disconnected once every clone has been dropped.
+#[derive(Debug)]
+pub struct KillableSender<T> {
+    tx: Arc<Sender<Option<T>>>,
+}
+
+impl<T> KillableSender<T> {
+    /// Send `message`, as with [`Sender::send()`]. Fails, handing the message back, once the
+    /// receiver has been dropped
+    pub fn send(&self, message: T) -> Result<(), SendError<T>> {
+        self.tx
+            .send(Some(message))
+            .map_err(|SendError(message)| SendError(message.expect("only messages are sent")))
+    }
+}
+
+impl<T> Clone for KillableSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+        }
+    }
+}
+
+/// Receiving half of a channel created by [`KillSwitchWatcher::channel()`], which wakes up as
+/// soon as the kill switch is flipped
+#[derive(Debug)]
+pub struct KillableReceiver<T> {
+    /// `None` is the wake-up sent on kill
+    rx: Receiver<Option<T>>,
+    watcher: KillSwitchWatcher,
+    _on_kill: CallbackHandle,
+}
+
+impl<T> KillableReceiver<T> {
+    /// Block until a message arrives, returning early with [`RecvOrKilled::Killed`] as soon as
+    /// the kill switch is flipped. Once the switch has been flipped this fails even if messages
+    /// are waiting, so that shutdown is not held up by a busy channel
+    pub fn recv(&self) -> Result<T, RecvOrKilled> {
+        self.recv_until(None)
+    }
+
+    /// Block until a message arrives, returning early with [`RecvOrKilled::Killed`] as soon as
+    /// the kill switch is flipped, or [`RecvOrKilled::Timeout`] once `timeout` has elapsed
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvOrKilled> {
+        self.recv_until(Instant::now().checked_add(timeout))
+    }
+
+    /// Iterate over messages, blocking for each one as with [`recv()`](Self::recv). Iteration ends
+    /// once the kill switch is flipped or every sender has been dropped
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(|| self.recv().ok())
+    }
+
+    fn recv_until(&self, deadline: Option<Instant>) -> Result<T, RecvOrKilled> {
+        loop {
+            if !self.watcher.is_alive() {
+                return Err(RecvOrKilled::Killed);
+            }
+            let received = match deadline {
+                None => self.rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                Some(deadline) => self
+                    .rx
+                    .recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            };
+            match received {
+                Ok(Some(message)) => return Ok(message),
+                // The wake-up sent on kill, so go round again to report it
+                Ok(None) => {}
+                Err(RecvTimeoutError::Timeout) => return Err(RecvOrKilled::Timeout),
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(match self.watcher.is_alive() {
+                        true => RecvOrKilled::Disconnected,
+                        false => RecvOrKilled::Killed,
+                    })
+                }
+            }
+        }
+    }
+}
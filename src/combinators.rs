@@ -0,0 +1,104 @@
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::sync::atomic::{AtomicUsize, Ordering::AcqRel};
+
+use crate::{CallbackHandle, Inner, KillSwitchWatcher};
+
+impl KillSwitchWatcher {
+    /// Produce a watcher which is killed as soon as any one of `watchers` is killed, carrying
+    /// over that watcher's reason. If `watchers` is empty, the result is never killed.
+    ///
+    /// The result supports everything a normal watcher does, including `wait()` and `killed()`,
+    /// and stops tracking its inputs once it is dropped
+    pub fn any_of(watchers: &[KillSwitchWatcher]) -> KillSwitchWatcher {
+        Self::combine(watchers, 1)
+    }
+
+    /// Produce a watcher which is killed once every one of `watchers` has been killed, carrying
+    /// over the reason of the last one to be killed. If `watchers` is empty, the result is
+    /// killed immediately.
+    ///
+    /// The result supports everything a normal watcher does, including `wait()` and `killed()`,
+    /// and stops tracking its inputs once it is dropped
+    pub fn all_of(watchers: &[KillSwitchWatcher]) -> KillSwitchWatcher {
+        if watchers.is_empty() {
+            let inner = Inner::new();
+            let _ = inner.kill(None);
+            return KillSwitchWatcher {
+                inner: Arc::new(inner).into(),
+            };
+        }
+        Self::combine(watchers, watchers.len())
+    }
+
+    /// Block the current thread until any one of `watchers` has been killed, returning the index
+    /// of the first one to be killed. If several have already been killed, the lowest index is
+    /// returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `watchers` is empty, as the wait would never finish
+    #[cfg(feature = "std")]
+    pub fn wait_any(watchers: &[KillSwitchWatcher]) -> usize {
+        use core::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+        use std::thread;
+
+        assert!(
+            !watchers.is_empty(),
+            "wait_any() needs at least one watcher"
+        );
+
+        const NONE: usize = usize::MAX;
+        let first = Arc::new(AtomicUsize::new(NONE));
+        let current = thread::current();
+        let _handles: Vec<CallbackHandle> = watchers
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                let first = first.clone();
+                let current = current.clone();
+                w.inner.on_kill(Box::new(move || {
+                    if first.compare_exchange(NONE, i, Release, Relaxed).is_ok() {
+                        current.unpark();
+                    }
+                }))
+            })
+            .collect();
+
+        loop {
+            match first.load(Acquire) {
+                NONE => thread::park(),
+                i => return i,
+            }
+        }
+    }
+
+    /// Derive a watcher which is killed once `needed` of `watchers` have been killed
+    fn combine(watchers: &[KillSwitchWatcher], needed: usize) -> KillSwitchWatcher {
+        let derived = Arc::new(Inner::new());
+        let remaining = Arc::new(AtomicUsize::new(needed));
+
+        // Each input only holds weak references, so that dropping the derived watcher releases
+        // it, and dropping the derived watcher drops the handles which unregister the callbacks
+        let links: Vec<CallbackHandle> = watchers
+            .iter()
+            .map(|w| {
+                let input = w.inner.downgrade();
+                let derived = Arc::downgrade(&derived);
+                let remaining = remaining.clone();
+                w.inner.on_kill(Box::new(move || {
+                    if remaining.fetch_sub(1, AcqRel) != 1 {
+                        return;
+                    }
+                    if let (Some(input), Some(derived)) = (input.upgrade(), derived.upgrade()) {
+                        let _ = derived.kill(input.reason());
+                    }
+                }))
+            })
+            .collect();
+        derived.lock().links = links;
+
+        KillSwitchWatcher {
+            inner: derived.into(),
+        }
+    }
+}
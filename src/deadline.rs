@@ -0,0 +1,128 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{timer, Inner, KillSwitch, KillSwitchWatcher};
+
+/// Kill reason recorded when a kill switch is flipped because its deadline passed, as set by
+/// [`KillSwitch::with_deadline()`], [`KillSwitch::kill_at()`] or [`KillSwitch::kill_after()`].
+/// Retrieve it from [`KillSwitchWatcher::reason()`] with `downcast_ref::<DeadlineExpired>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlineExpired {
+    /// The deadline which passed
+    pub deadline: Instant,
+}
+
+impl Error for DeadlineExpired {}
+
+impl Display for DeadlineExpired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "kill switch deadline expired")
+    }
+}
+
+impl Inner {
+    /// Arrange for the switch to be killed at `deadline`, keeping whichever of this and any
+    /// existing deadline is earlier.
+    fn kill_at(self: &Arc<Self>, deadline: Instant) {
+        {
+            let mut state = self.lock();
+            if !self.is_alive() || state.deadline.is_some_and(|d| d <= deadline) {
+                return;
+            }
+            state.deadline = Some(deadline);
+        }
+
+        let reason = Arc::new(DeadlineExpired { deadline });
+        if deadline <= Instant::now() {
+            let _ = self.kill(Some(reason));
+            return;
+        }
+        // The timer only holds a weak reference, so that a pending deadline does not keep the
+        // switch alive after every handle has been dropped
+        let inner = Arc::downgrade(self);
+        let timer = timer::schedule_cancellable(deadline, move || {
+            if let Some(inner) = inner.upgrade() {
+                let _ = inner.kill(Some(reason));
+            }
+        });
+
+        // The handle lives in the state, so the timer entry is cancelled once the deadline is
+        // replaced by an earlier one, the switch is killed, or the switch is dropped
+        let mut state = self.lock();
+        if self.is_alive() && state.deadline == Some(deadline) {
+            state.deadline_timer = Some(timer);
+        }
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        self.lock().deadline
+    }
+}
+
+impl KillSwitch {
+    /// Create a new kill switch which is flipped automatically once `deadline` has passed, with a
+    /// [`DeadlineExpired`] reason.
+    ///
+    /// Deadlines for every kill switch in the process are tracked by a single background thread,
+    /// which is started the first time a deadline is set.
+    pub fn with_deadline(deadline: Instant) -> KillSwitch {
+        let kill = KillSwitch::default();
+        kill.kill_at(deadline);
+        kill
+    }
+
+    /// Flip the kill switch automatically once `deadline` has passed, with a [`DeadlineExpired`]
+    /// reason. If the switch already has a deadline, the earlier of the two is kept. Has no effect
+    /// if the switch has already been flipped
+    ///
+    /// Each deadline is an entry in the background thread's queue. Once it can no longer fire,
+    /// because the switch was killed or dropped or given an earlier deadline, the entry is
+    /// cancelled, and cancelled entries are cleared out in batches as new deadlines are set. So
+    /// the queue stays proportional to the number of pending deadlines, even with many
+    /// short-lived switches given long deadlines
+    pub fn kill_at(&self, deadline: Instant) {
+        self.inner.kill_at(deadline)
+    }
+
+    /// Flip the kill switch automatically once `timeout` has elapsed. See
+    /// [`kill_at()`](Self::kill_at) for details
+    pub fn kill_after(&self, timeout: Duration) {
+        // A deadline too far away to represent will never be reached
+        if let Some(deadline) = Instant::now().checked_add(timeout) {
+            self.kill_at(deadline)
+        }
+    }
+
+    /// The instant at which the kill switch will be flipped automatically, if a deadline has been
+    /// set
+    pub fn deadline(&self) -> Option<Instant> {
+        self.inner.deadline()
+    }
+
+    /// The time left until the kill switch will be flipped automatically, if a deadline has been
+    /// set. Returns zero once the deadline has passed
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline()
+            .map(|d| d.saturating_duration_since(Instant::now()))
+    }
+}
+
+impl KillSwitchWatcher {
+    /// The instant at which the kill switch will be flipped automatically, if a deadline has been
+    /// set
+    pub fn deadline(&self) -> Option<Instant> {
+        self.inner.deadline()
+    }
+
+    /// The time left until the kill switch will be flipped automatically, if a deadline has been
+    /// set. Returns zero once the deadline has passed, which makes it convenient for sizing
+    /// timeouts on work done before the deadline
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline()
+            .map(|d| d.saturating_duration_since(Instant::now()))
+    }
+}
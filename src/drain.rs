@@ -0,0 +1,110 @@
+use core::sync::atomic::Ordering::{Relaxed, Release, SeqCst};
+#[cfg(feature = "std")]
+use std::{
+    sync::{atomic::Ordering::Acquire, PoisonError},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "std")]
+use crate::KillSwitch;
+use crate::{sync::fence, InnerRef, KillSwitchWatcher};
+
+/// Marks an operation as in flight for as long as it is held, so that
+/// [`KillSwitch::kill_and_drain()`](crate::KillSwitch::kill_and_drain) can wait for it to finish.
+/// Obtained from [`KillSwitchWatcher::enter()`].
+#[derive(Debug)]
+#[must_use = "the operation is only counted as in flight while the guard is held"]
+pub struct OperationGuard {
+    inner: InnerRef,
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        if self.inner.in_flight.fetch_sub(1, Release) != 1 {
+            return;
+        }
+        // Pairs with the fence in `kill_and_drain()`, as in `enter()`: either the drainer sees
+        // this decrement, or this sees the switch as killed and wakes the drainer
+        fence(SeqCst);
+        if !self.inner.is_alive() {
+            // Taking the lock means a drainer is either yet to check the count, or already
+            // waiting on the condition variable
+            let _state = self.inner.lock();
+            #[cfg(feature = "std")]
+            self.inner.cvar.notify_all();
+        }
+    }
+}
+
+/// Outcome of [`KillSwitch::kill_and_drain()`](crate::KillSwitch::kill_and_drain)
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrainResult {
+    /// Every [`OperationGuard`] was dropped
+    Drained,
+    /// The timeout elapsed with operations still running
+    TimedOut {
+        /// Number of guards still held when the timeout elapsed
+        in_flight: usize,
+    },
+}
+
+impl KillSwitchWatcher {
+    /// Start an operation which the kill switch should wait for, returning a guard which keeps it
+    /// counted as in flight until dropped. Returns `None` once the switch has been flipped, so that
+    /// no new work starts during shutdown.
+    ///
+    /// Once [`KillSwitch::kill_and_drain()`](crate::KillSwitch::kill_and_drain) has flipped the
+    /// switch, every later call returns `None`, so the count it waits on can only go down
+    pub fn enter(&self) -> Option<OperationGuard> {
+        self.inner.in_flight.fetch_add(1, Relaxed);
+        let guard = OperationGuard {
+            inner: self.inner.clone(),
+        };
+        // Pairs with the fence in `kill_and_drain()`: either the drainer sees this increment, or
+        // this sees the switch as killed
+        fence(SeqCst);
+        match self.is_alive() {
+            true => Some(guard),
+            false => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl KillSwitch {
+    /// Flip the kill switch, then block until every [`OperationGuard`] obtained from
+    /// [`KillSwitchWatcher::enter()`] has been dropped, or until `timeout` has elapsed. If the
+    /// switch has already been flipped, this only waits.
+    pub fn kill_and_drain(&self, timeout: Duration) -> DrainResult {
+        let _ = self.kill();
+        fence(SeqCst);
+
+        let deadline = Instant::now().checked_add(timeout);
+        let inner = &self.inner;
+        let mut guard = inner.lock();
+        loop {
+            let in_flight = inner.in_flight.load(Acquire);
+            if in_flight == 0 {
+                return DrainResult::Drained;
+            }
+            guard = match deadline {
+                None => inner
+                    .cvar
+                    .wait(guard)
+                    .unwrap_or_else(PoisonError::into_inner),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return DrainResult::TimedOut { in_flight };
+                    }
+                    inner
+                        .cvar
+                        .wait_timeout(guard, deadline - now)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+            };
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::InnerRef;
+
+/// Future returned by [`KillSwitch::killed()`](crate::KillSwitch::killed) and
+/// [`KillSwitchWatcher::killed()`](crate::KillSwitchWatcher::killed), which resolves once the kill
+/// switch has been flipped.
+///
+/// While pending, the future keeps its most recent [`Waker`](core::task::Waker) registered with the
+/// kill switch, and removes it again when dropped.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct KilledFuture {
+    inner: InnerRef,
+    id: Option<u64>,
+}
+
+impl KilledFuture {
+    pub(crate) fn new(inner: InnerRef) -> Self {
+        Self { inner, id: None }
+    }
+}
+
+impl Future for KilledFuture {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if !self.inner.is_alive() {
+            return Poll::Ready(());
+        }
+
+        let this = &mut *self;
+        let mut state = this.inner.lock();
+        // The kill clears the flag and drains the wakers under this same lock, so checking it
+        // again here means a kill can never slip in between the check and registering
+        if !this.inner.is_alive() {
+            if let Some(id) = this.id.take() {
+                state.wakers.remove(&id);
+            }
+            return Poll::Ready(());
+        }
+
+        let id = *this.id.get_or_insert_with(|| state.next_id());
+        match state.wakers.get_mut(&id) {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            Some(waker) => waker.clone_from(cx.waker()),
+            None => {
+                state.wakers.insert(id, cx.waker().clone());
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for KilledFuture {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            // Dropped only once the lock is released, in case dropping the waker touches the
+            // switch
+            let removed = self.inner.lock().wakers.remove(&id);
+            drop(removed);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use core::{
+    fmt::Debug,
+    iter::{Chain, FusedIterator},
+    option,
+};
+
+use crate::KillSwitchWatcher;
+
+/// Extension trait adding [`until_killed()`](IteratorExt::until_killed) to every iterator
+pub trait IteratorExt: Iterator + Sized {
+    /// Stop iterating once the kill switch behind `watcher` has been flipped. By default the
+    /// switch is checked before every item; use [`UntilKilled::check_every()`] to check less
+    /// often in hot loops.
+    ///
+    /// Iterate over a mutable reference to find out afterwards whether the iteration was cut
+    /// short:
+    ///
+    /// ```
+    /// use killswitch_std::{IteratorExt, KillSwitch};
+    ///
+    /// let kill = KillSwitch::default();
+    /// let mut items = (0..1_000_000).until_killed(kill.watcher()).check_every(64);
+    ///
+    /// for i in &mut items {
+    ///     if i == 1000 {
+    ///         kill.kill().unwrap();
+    ///     }
+    /// }
+    /// assert!(items.was_killed());
+    /// ```
+    fn until_killed(self, watcher: KillSwitchWatcher) -> UntilKilled<Self> {
+        UntilKilled {
+            iter: self,
+            watcher,
+            stride: 1,
+            countdown: 0,
+            withheld: None,
+        }
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
+
+/// Iterator returned by [`IteratorExt::until_killed()`]
+pub struct UntilKilled<I: Iterator> {
+    iter: I,
+    watcher: KillSwitchWatcher,
+    /// Number of items between checks of the kill switch
+    stride: usize,
+    /// Items left before the next check
+    countdown: usize,
+    /// The item held back when the switch was found to be killed. Only set if there actually was
+    /// another item, so that running out at the same time as a kill does not count as being cut
+    /// short
+    withheld: Option<I::Item>,
+}
+
+impl<I: Iterator> UntilKilled<I> {
+    /// Check the kill switch only before every `n`th item, so at most `n - 1` further items are
+    /// yielded after a kill.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is 0
+    pub fn check_every(mut self, n: usize) -> Self {
+        assert!(n > 0, "kill switch check stride must be at least 1");
+        self.stride = n;
+        self.countdown = self.countdown.min(n);
+        self
+    }
+
+    /// Whether iteration stopped because the kill switch was flipped, rather than because the
+    /// underlying iterator ran out. When the kill is noticed the next item is fetched to tell the
+    /// two apart, so this is `false` if the iterator was about to run out anyway
+    pub fn was_killed(&self) -> bool {
+        self.withheld.is_some()
+    }
+
+    /// Recover the rest of the underlying iterator, starting with the item held back when the
+    /// kill was noticed, if any
+    pub fn into_inner(self) -> Chain<option::IntoIter<I::Item>, I> {
+        self.withheld.into_iter().chain(self.iter)
+    }
+}
+
+impl<I: Iterator> Iterator for UntilKilled<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        if self.withheld.is_some() {
+            return None;
+        }
+        if self.countdown == 0 {
+            if !self.watcher.is_alive() {
+                self.withheld = self.iter.next();
+                return None;
+            }
+            self.countdown = self.stride;
+        }
+        self.countdown -= 1;
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.withheld {
+            Some(_) => (0, Some(0)),
+            None => (0, self.iter.size_hint().1),
+        }
+    }
+}
+
+impl<I: Iterator + Clone> Clone for UntilKilled<I>
+where
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            watcher: self.watcher.clone(),
+            stride: self.stride,
+            countdown: self.countdown,
+            withheld: self.withheld.clone(),
+        }
+    }
+}
+
+impl<I: Iterator + Debug> Debug for UntilKilled<I>
+where
+    I::Item: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("UntilKilled")
+            .field("iter", &self.iter)
+            .field("watcher", &self.watcher)
+            .field("stride", &self.stride)
+            .field("countdown", &self.countdown)
+            .field("withheld", &self.withheld)
+            .finish()
+    }
+}
+
+impl<I: FusedIterator> FusedIterator for UntilKilled<I> {}
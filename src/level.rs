@@ -0,0 +1,153 @@
+use alloc::vec::Vec;
+use core::{
+    fmt::Display,
+    sync::atomic::Ordering::{Acquire, Release},
+};
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+use crate::{Inner, KillSwitch, KillSwitchWatcher};
+
+/// How far along shutdown a kill switch is. Levels are ordered, and a switch only ever moves
+/// forward through them with [`KillSwitch::advance()`], from [`Level::ALIVE`] up to
+/// [`Level::KILLED`], which is reached by flipping the switch.
+///
+/// The named levels in between cover the common case of a service which first stops accepting new
+/// work, and then stops the work in progress, but any value can be used for finer grained stages.
+///
+/// ```
+/// use killswitch_std::{KillSwitch, Level};
+///
+/// let kill = KillSwitch::default();
+/// let watcher = kill.watcher();
+///
+/// assert!(kill.advance(Level::DRAINING));
+/// assert_eq!(watcher.level(), Level::DRAINING);
+/// assert!(watcher.is_alive());
+///
+/// // Levels never go backwards
+/// assert!(!kill.advance(Level::ALIVE));
+///
+/// kill.kill().unwrap();
+/// assert_eq!(watcher.level(), Level::KILLED);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Level(pub u8);
+
+impl Level {
+    /// Level of a newly created switch
+    pub const ALIVE: Level = Level(0);
+    /// No new work should be accepted, but work already in progress may finish
+    pub const DRAINING: Level = Level(1);
+    /// Work in progress should be wound down
+    pub const STOPPING: Level = Level(2);
+    /// The switch has been flipped, and `is_alive()` returns `false`
+    pub const KILLED: Level = Level(u8::MAX);
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Level::ALIVE => write!(f, "alive"),
+            Level::DRAINING => write!(f, "draining"),
+            Level::STOPPING => write!(f, "stopping"),
+            Level::KILLED => write!(f, "killed"),
+            Level(level) => write!(f, "level {level}"),
+        }
+    }
+}
+
+impl Inner {
+    /// The flag is checked first, so that a killed switch always reports [`Level::KILLED`]
+    pub(crate) fn level(&self) -> Level {
+        if !self.is_alive() {
+            return Level::KILLED;
+        }
+        Level(self.level.load(Acquire))
+    }
+
+    /// Raise the level to `level` here and in every child. Reaching [`Level::KILLED`] kills the
+    /// switch, so the flag stays the single source of truth for being dead.
+    fn advance(&self, level: Level) -> bool {
+        if level == Level::KILLED {
+            return self.kill(None).is_ok();
+        }
+        let children: Vec<_> = {
+            let state = self.lock();
+            if self.level() >= level {
+                return false;
+            }
+            self.level.store(level.0, Release);
+            #[cfg(feature = "std")]
+            self.cvar.notify_all();
+            state
+                .children
+                .values()
+                .filter_map(|c| c.upgrade())
+                .collect()
+        };
+        for child in children {
+            child.advance(level);
+        }
+        true
+    }
+}
+
+impl KillSwitchWatcher {
+    /// The current [`Level`] of the kill switch. This is [`Level::KILLED`] once it has been
+    /// flipped, so `level() < Level::KILLED` exactly when `is_alive()` is `true`
+    pub fn level(&self) -> Level {
+        self.inner.level()
+    }
+
+    /// Block the current thread until the kill switch has reached at least `level`. Returns
+    /// immediately if it already has.
+    #[cfg(feature = "std")]
+    pub fn wait_for_level(&self, level: Level) {
+        self.inner.wait_for_level(level, None);
+    }
+
+    /// Block the current thread until the kill switch has reached at least `level`, or until
+    /// `timeout` has elapsed. Returns `true` if the level was reached, and `false` if the wait
+    /// timed out
+    #[cfg(feature = "std")]
+    pub fn wait_for_level_timeout(&self, level: Level, timeout: Duration) -> bool {
+        self.inner
+            .wait_for_level(level, std::time::Instant::now().checked_add(timeout))
+    }
+}
+
+impl KillSwitch {
+    /// The current [`Level`] of the kill switch. This is [`Level::KILLED`] once it has been
+    /// flipped, so `level() < Level::KILLED` exactly when `is_alive()` is `true`
+    pub fn level(&self) -> Level {
+        self.inner.level()
+    }
+
+    /// Move the kill switch forward to `level`, waking any threads blocked in
+    /// `wait_for_level()`. Children created with `child()` are advanced too, and new children
+    /// start at the level of their parent. Advancing to
+    /// [`Level::KILLED`] is the same as `kill()`.
+    ///
+    /// Levels only move forward: returns `false`, leaving the switch unchanged, if it has already
+    /// reached `level` or a later one
+    pub fn advance(&self, level: Level) -> bool {
+        self.inner.advance(level)
+    }
+
+    /// Block the current thread until the kill switch has reached at least `level`. Returns
+    /// immediately if it already has.
+    #[cfg(feature = "std")]
+    pub fn wait_for_level(&self, level: Level) {
+        self.inner.wait_for_level(level, None);
+    }
+
+    /// Block the current thread until the kill switch has reached at least `level`, or until
+    /// `timeout` has elapsed. Returns `true` if the level was reached, and `false` if the wait
+    /// timed out
+    #[cfg(feature = "std")]
+    pub fn wait_for_level_timeout(&self, level: Level, timeout: Duration) -> bool {
+        self.inner
+            .wait_for_level(level, std::time::Instant::now().checked_add(timeout))
+    }
+}
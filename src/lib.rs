@@ -1,20 +1,400 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
 
-use std::{
+extern crate alloc;
+
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+use core::{
+    error::Error,
     fmt::Display,
-    sync::{
-        atomic::{AtomicBool, Ordering::Relaxed},
-        Arc,
-    },
+    ops::Deref,
+    sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release},
+    task::Waker,
 };
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+#[cfg(feature = "std")]
+use sync::Condvar;
+use sync::{AtomicBool, AtomicU8, AtomicUsize, Mutex, MutexGuard};
+
+mod callback;
+#[cfg(feature = "std")]
+mod channel;
+mod combinators;
+#[cfg(all(feature = "std", not(loom)))]
+mod deadline;
+mod drain;
+mod future;
+mod iter;
+mod level;
+#[cfg(feature = "std")]
+mod panic;
+#[cfg(feature = "std")]
+mod park;
+#[cfg(feature = "std")]
+mod queue;
+mod resettable;
+// The raw constants in `signals::sys` are only verified for these architectures
+#[cfg(all(
+    feature = "signals",
+    target_os = "linux",
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    ),
+    not(loom)
+))]
+mod signals;
+#[cfg(not(loom))]
+mod static_switch;
+mod sync;
+#[cfg(all(feature = "std", not(loom)))]
+mod timer;
+#[cfg(feature = "std")]
+mod wait;
+#[cfg(all(feature = "std", not(loom)))]
+mod watchdog;
+
+pub use callback::CallbackHandle;
+#[cfg(feature = "std")]
+pub use channel::{KillableReceiver, KillableSender, RecvIter, RecvOrKilled};
+#[cfg(all(feature = "std", not(loom)))]
+pub use deadline::DeadlineExpired;
+#[cfg(feature = "std")]
+pub use drain::DrainResult;
+pub use drain::OperationGuard;
+pub use future::KilledFuture;
+pub use iter::{IteratorExt, UntilKilled};
+pub use level::Level;
+#[cfg(feature = "std")]
+pub use panic::{KillOnDrop, Panicked};
+#[cfg(feature = "std")]
+pub use park::ParkRegistration;
+#[cfg(feature = "std")]
+pub use queue::{DrainMode, KillableQueue};
+pub use resettable::{ResettableKillSwitch, Superseded};
+#[cfg(all(
+    feature = "signals",
+    target_os = "linux",
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    ),
+    not(loom)
+))]
+pub use signals::{Escalation, Signalled, SIGHUP, SIGINT, SIGTERM};
+#[cfg(not(loom))]
+pub use static_switch::StaticKillSwitch;
+#[cfg(feature = "std")]
+pub use wait::Killed;
+#[cfg(all(feature = "std", not(loom)))]
+pub use watchdog::{Feeder, MissedFeed, Watchdog};
+
+use callback::Callback;
+
+/// The cause recorded when a [`KillSwitch`] is flipped with [`KillSwitch::kill_with()`]. This is
+/// reference counted, so can be cheaply handed out to every watcher.
+pub type KillReason = Arc<dyn Error + Send + Sync + 'static>;
+
+/// Shared state behind every [`KillSwitch`] and [`KillSwitchWatcher`]. The atomic flag is the fast
+/// path for `is_alive()`, while the mutex and condition variable are only touched by threads which
+/// want to block (or tasks which want to be woken) until the switch is flipped.
+#[derive(Debug)]
+struct Inner {
+    alive: AtomicBool,
+    /// The [`Level`] reached by [`KillSwitch::advance()`], only meaningful while `alive` is set
+    level: AtomicU8,
+    state: Mutex<State>,
+    #[cfg(feature = "std")]
+    cvar: Condvar,
+    /// Number of [`KillSwitch`] handles, not counting watchers
+    owners: AtomicUsize,
+    /// Number of live [`OperationGuard`]s
+    in_flight: AtomicUsize,
+    /// Set by [`KillSwitch::kill_when_orphaned()`]
+    kill_on_orphan: AtomicBool,
+    /// Incremented by [`ResettableKillSwitch::reset()`] for each new switch it creates, and 0
+    /// otherwise
+    generation: u64,
+    /// Set for switches created by [`KillSwitch::child()`], so that the child can remove itself
+    /// from the parent's bookkeeping once it is dropped
+    parent: Option<(Weak<Inner>, u64)>,
+}
+
+/// Reference to the shared state of a switch, which lives either on the heap for a
+/// [`KillSwitch`], or in a `static` for a [`StaticKillSwitch`].
+#[derive(Clone, Debug)]
+enum InnerRef {
+    Heap(Arc<Inner>),
+    Static(&'static Inner),
+}
+
+/// Non-owning counterpart to [`InnerRef`], used by registrations which should not keep the switch
+/// alive.
+#[derive(Clone, Debug)]
+enum WeakInnerRef {
+    Heap(Weak<Inner>),
+    Static(&'static Inner),
+}
+
+impl Deref for InnerRef {
+    type Target = Inner;
+
+    fn deref(&self) -> &Inner {
+        match self {
+            InnerRef::Heap(inner) => inner,
+            InnerRef::Static(inner) => inner,
+        }
+    }
+}
+
+impl From<Arc<Inner>> for InnerRef {
+    fn from(inner: Arc<Inner>) -> Self {
+        InnerRef::Heap(inner)
+    }
+}
+
+impl InnerRef {
+    fn downgrade(&self) -> WeakInnerRef {
+        match self {
+            InnerRef::Heap(inner) => WeakInnerRef::Heap(Arc::downgrade(inner)),
+            InnerRef::Static(inner) => WeakInnerRef::Static(inner),
+        }
+    }
+
+    /// Register `f` to be called by whichever thread kills the switch, or call it immediately if
+    /// the switch is already dead.
+    fn on_kill(&self, f: Box<dyn FnOnce() + Send>) -> CallbackHandle {
+        let mut state = self.lock();
+        if !self.is_alive() {
+            drop(state);
+            Callback(f).call();
+            return CallbackHandle::inert();
+        }
+        let id = state.next_id();
+        state.callbacks.insert(id, Callback(f));
+        CallbackHandle::new(self.downgrade(), id)
+    }
+}
+
+impl WeakInnerRef {
+    fn upgrade(&self) -> Option<InnerRef> {
+        match self {
+            WeakInnerRef::Heap(inner) => inner.upgrade().map(InnerRef::Heap),
+            WeakInnerRef::Static(inner) => Some(InnerRef::Static(inner)),
+        }
+    }
+}
+
+/// Declare a `const fn`, except when built for loom, whose primitives cannot be created in a const
+/// context.
+macro_rules! const_unless_loom {
+    ($(#[$meta:meta])* $vis:vis fn $($rest:tt)*) => {
+        #[cfg(not(loom))]
+        $(#[$meta])* $vis const fn $($rest)*
+        #[cfg(loom)]
+        $(#[$meta])* $vis fn $($rest)*
+    };
+}
+
+/// Bookkeeping for everything waiting on the switch, guarded by [`Inner::state`].
+#[derive(Debug)]
+struct State {
+    next_id: u64,
+    wakers: BTreeMap<u64, Waker>,
+    children: BTreeMap<u64, Weak<Inner>>,
+    callbacks: BTreeMap<u64, Callback>,
+    /// Callbacks registered on the inputs of a watcher made with
+    /// [`KillSwitchWatcher::any_of()`] or [`KillSwitchWatcher::all_of()`], which are unregistered
+    /// when it is dropped
+    links: Vec<CallbackHandle>,
+    reason: Option<KillReason>,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+    /// Cancels the timer entry for `deadline` when replaced or dropped
+    #[cfg(all(feature = "std", not(loom)))]
+    deadline_timer: Option<timer::TimerHandle>,
+}
+
+impl State {
+    const fn new() -> Self {
+        Self {
+            next_id: 0,
+            wakers: BTreeMap::new(),
+            children: BTreeMap::new(),
+            callbacks: BTreeMap::new(),
+            links: Vec::new(),
+            reason: None,
+            #[cfg(feature = "std")]
+            deadline: None,
+            #[cfg(all(feature = "std", not(loom)))]
+            deadline_timer: None,
+        }
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+impl Inner {
+    const_unless_loom! {
+        fn new() -> Self {
+            Self::with_parent(None)
+        }
+    }
+
+    const_unless_loom! {
+        fn with_parent(parent: Option<(Weak<Inner>, u64)>) -> Self {
+            Self {
+                alive: AtomicBool::new(true),
+                level: AtomicU8::new(Level::ALIVE.0),
+                state: Mutex::new(State::new()),
+                #[cfg(feature = "std")]
+                cvar: Condvar::new(),
+                owners: AtomicUsize::new(0),
+                in_flight: AtomicUsize::new(0),
+                kill_on_orphan: AtomicBool::new(false),
+                generation: 0,
+                parent,
+            }
+        }
+    }
+
+    /// Create a new switch which will be killed along with `self`, starting at the same level and
+    /// in the same generation. If `self` has already been killed, then so is the child.
+    fn child(self: &Arc<Self>) -> Arc<Self> {
+        let mut state = self.lock();
+        if !self.is_alive() {
+            let mut child = Self::new();
+            child.generation = self.generation;
+            child.lock().reason = state.reason.clone();
+            child.alive.store(false, Relaxed);
+            return Arc::new(child);
+        }
+
+        let id = state.next_id();
+        let mut child = Self::with_parent(Some((Arc::downgrade(self), id)));
+        child.generation = self.generation;
+        child.level.store(self.level.load(Relaxed), Relaxed);
+        let child = Arc::new(child);
+        state.children.insert(id, Arc::downgrade(&child));
+        child
+    }
+
+    /// Flip the switch. Deciding the winner is a single `swap` of the flag, made while holding the
+    /// lock so that the winner's reason is in place before anyone else can read it.
+    fn kill(&self, reason: Option<KillReason>) -> Result<KillToken, KillSwitchErr> {
+        let pending = self.kill_deferred(reason)?;
+        pending.run();
+        Ok(KillToken { _private: () })
+    }
+
+    /// Flip the switch and wake threads blocked in `wait()`, but leave waking tasks, running
+    /// callbacks and killing children to the caller, for when running arbitrary code on this
+    /// thread is unsafe.
+    fn kill_deferred(&self, reason: Option<KillReason>) -> Result<PendingKill, KillSwitchErr> {
+        let mut state = self.lock();
+        if !self.alive.swap(false, AcqRel) {
+            return Err(KillSwitchErr::AlreadyKilled(state.reason.clone()));
+        }
+        state.reason = reason.clone();
+        #[cfg(all(feature = "std", not(loom)))]
+        {
+            state.deadline_timer = None;
+        }
+        // Taking the lock before clearing the flag guarantees that no waiter can be between
+        // checking the flag and going to sleep
+        #[cfg(feature = "std")]
+        self.cvar.notify_all();
+        Ok(PendingKill {
+            reason,
+            wakers: core::mem::take(&mut state.wakers),
+            callbacks: core::mem::take(&mut state.callbacks),
+            children: core::mem::take(&mut state.children),
+        })
+    }
+
+    fn reason(&self) -> Option<KillReason> {
+        self.lock().reason.clone()
+    }
+
+    /// Pairs with the release half of the `swap` in `kill()`, so that everything written before
+    /// the kill is visible once the switch is seen to be dead
+    fn is_alive(&self) -> bool {
+        self.alive.load(Acquire)
+    }
+
+    /// Nothing in [`State`] can be left half-updated by a panic, so a poisoned lock can safely be
+    /// recovered.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        sync::lock(&self.state)
+    }
+}
+
+/// The work left over after a switch has been flipped by [`Inner::kill_deferred()`]
+#[derive(Debug)]
+struct PendingKill {
+    reason: Option<KillReason>,
+    wakers: BTreeMap<u64, Waker>,
+    callbacks: BTreeMap<u64, Callback>,
+    children: BTreeMap<u64, Weak<Inner>>,
+}
+
+impl PendingKill {
+    fn run(self) {
+        for waker in self.wakers.into_values() {
+            waker.wake();
+        }
+        for callback in self.callbacks.into_values() {
+            callback.call();
+        }
+        for child in self.children.into_values().filter_map(|c| c.upgrade()) {
+            let _ = child.kill(self.reason.clone());
+        }
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some((parent, id)) = self.parent.take() {
+            if let Some(parent) = parent.upgrade() {
+                parent.lock().children.remove(&id);
+            }
+        }
+    }
+}
 
 /// Convenience type which wraps a [`AtomicBool`].
 /// Initially, `is_alive()` will return `true`. The value can be cloned across threads, and once it
 /// has been `kill()`ed, then all of the clones will return `false` from `is_alive()`.
-#[derive(Clone, Debug)]
+///
+/// # Memory ordering
+///
+/// Flipping the switch has release semantics, and observing it has acquire semantics: everything
+/// a thread writes before a successful `kill()` or `kill_with()` happens-before any point where
+/// another thread sees the switch as killed. That is, after `is_alive()` returns `false`,
+/// `wait()` returns, `wait_timeout()` returns `true`, or `killed()` resolves, on either the
+/// switch or any of its watchers. The same holds for children killed along with their parent.
+/// These guarantees are model-checked with [loom](https://docs.rs/loom) in `tests/loom.rs`.
+#[derive(Debug)]
 pub struct KillSwitch {
-    switch: Arc<AtomicBool>,
+    inner: Arc<Inner>,
 }
 
 /// Derived from a [`KillSwitch`], allows to check if the kill switch is still alive, but cannot
@@ -22,52 +402,143 @@ pub struct KillSwitch {
 /// the kill switch.
 #[derive(Clone, Debug)]
 pub struct KillSwitchWatcher {
-    switch: Arc<AtomicBool>,
+    inner: InnerRef,
 }
 
 impl KillSwitchWatcher {
     /// Check if the kill switch has been flipped. Before flipping will return `true`, and
     /// afterwards will return `false`
     pub fn is_alive(&self) -> bool {
-        self.switch.load(Relaxed)
+        self.inner.is_alive()
+    }
+
+    /// Produce a future which resolves once the kill switch has been flipped. The future only
+    /// relies on the standard library, so can be awaited from any async runtime.
+    pub fn killed(&self) -> KilledFuture {
+        KilledFuture::new(self.inner.clone())
+    }
+
+    /// The reason the kill switch was flipped, if one was given to
+    /// [`KillSwitch::kill_with()`]. Returns `None` while the switch is alive, or if it was flipped
+    /// with a plain [`KillSwitch::kill()`]
+    pub fn reason(&self) -> Option<KillReason> {
+        self.inner.reason()
     }
 }
 impl KillSwitch {
+    /// Every handle must be created through here, so that the number of owners is tracked for
+    /// `kill_when_orphaned()`
+    fn from_inner(inner: Arc<Inner>) -> Self {
+        inner.owners.fetch_add(1, Relaxed);
+        Self { inner }
+    }
+
+    /// Create a new dead-man's kill switch, which is flipped automatically once every
+    /// [`KillSwitch`] handle to it has been dropped. Shorthand for `kill_when_orphaned()` on a new
+    /// switch
+    pub fn dead_man() -> KillSwitch {
+        let kill = KillSwitch::default();
+        kill.kill_when_orphaned();
+        kill
+    }
+
+    /// Opt in to flipping the kill switch automatically, with an [`Orphaned`] reason, once the
+    /// last [`KillSwitch`] handle to it is dropped. Watchers do not count as handles, so they are
+    /// never left waiting forever on a switch which nobody is able to flip any more (for example
+    /// because the controlling thread panicked or returned early)
+    pub fn kill_when_orphaned(&self) {
+        self.inner.kill_on_orphan.store(true, Release);
+    }
+
     /// Check if the kill switch has been flipped. Before flipping will return `true`, and
     /// afterwards will return `false`
     pub fn is_alive(&self) -> bool {
-        self.switch.load(Relaxed)
+        self.inner.is_alive()
     }
 
-    /// Flip the kill switch (will cause `is_alive()` to return `false`
-    pub fn kill(&self) -> Result<(), KillSwitchErr> {
-        match self.is_alive() {
-            true => {
-                self.switch.store(false, Relaxed);
-                Ok(())
-            }
-            false => Err(KillSwitchErr::AlreadyKilled),
-        }
+    /// Produce a future which resolves once the kill switch has been flipped. The future only
+    /// relies on the standard library, so can be awaited from any async runtime.
+    pub fn killed(&self) -> KilledFuture {
+        KilledFuture::new(self.inner.clone().into())
+    }
+
+    /// Flip the kill switch (will cause `is_alive()` to return `false`, and wake up any threads
+    /// blocked in `wait()` or `wait_timeout()`, or awaiting `killed()`). Callbacks registered with
+    /// `on_kill()` are run on the current thread, and any children created with `child()` are
+    /// killed too.
+    ///
+    /// Exactly one call succeeds, even when several threads race to flip the switch: the winner
+    /// receives a [`KillToken`], and every other caller receives [`KillSwitchErr::AlreadyKilled`]
+    pub fn kill(&self) -> Result<KillToken, KillSwitchErr> {
+        self.inner.kill(None)
+    }
+
+    /// Flip the kill switch as with `kill()`, recording `reason` as the cause. The reason is
+    /// available to every watcher through `reason()`, and is passed down to any children. Only
+    /// the first kill records a reason: if the switch has already been flipped, the error carries
+    /// the original reason instead
+    pub fn kill_with(
+        &self,
+        reason: impl Into<Box<dyn Error + Send + Sync + 'static>>,
+    ) -> Result<KillToken, KillSwitchErr> {
+        self.inner.kill(Some(reason.into().into()))
+    }
+
+    /// The reason the kill switch was flipped, if one was given to `kill_with()`. Returns `None`
+    /// while the switch is alive, or if it was flipped with a plain `kill()`
+    pub fn reason(&self) -> Option<KillReason> {
+        self.inner.reason()
+    }
+
+    /// Register a callback to run as soon as the kill switch is flipped. The callback is invoked
+    /// exactly once, on the thread which flips the switch, or immediately on the current thread if
+    /// the switch has already been flipped.
+    ///
+    /// The callback stays registered for as long as the returned [`CallbackHandle`] is held, and
+    /// dropping the handle unregisters it. With the `std` feature, a panic in one callback is
+    /// caught, so that it cannot stop the remaining callbacks from running or cause `kill()` to
+    /// panic
+    pub fn on_kill(&self, f: impl FnOnce() + Send + 'static) -> CallbackHandle {
+        InnerRef::from(self.inner.clone()).on_kill(Box::new(f))
+    }
+
+    /// Produce a new, independent kill switch which is killed automatically when this one is
+    /// killed. Killing the child does not affect this switch or any other children, and the child
+    /// is forgotten by this switch once the child and all of its watchers are dropped.
+    pub fn child(&self) -> KillSwitch {
+        KillSwitch::from_inner(self.inner.child())
     }
 
     /// Produce a kill switch which can only watch the value, but cannot flip the switch
     pub fn watcher(&self) -> KillSwitchWatcher {
         KillSwitchWatcher {
-            switch: self.switch.clone(),
+            inner: self.inner.clone().into(),
         }
     }
 }
 
 impl Default for KillSwitch {
     fn default() -> Self {
-        Self {
-            switch: Arc::new(AtomicBool::new(true)),
+        Self::from_inner(Arc::new(Inner::new()))
+    }
+}
+
+impl Clone for KillSwitch {
+    fn clone(&self) -> Self {
+        Self::from_inner(self.inner.clone())
+    }
+}
+
+impl Drop for KillSwitch {
+    fn drop(&mut self) {
+        if self.inner.owners.fetch_sub(1, AcqRel) == 1 && self.inner.kill_on_orphan.load(Acquire) {
+            let _ = self.inner.kill(Some(Arc::new(Orphaned)));
         }
     }
 }
 
 impl Display for KillSwitchWatcher {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "{}",
@@ -80,7 +551,7 @@ impl Display for KillSwitchWatcher {
 }
 
 impl Display for KillSwitch {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "{}",
@@ -92,23 +563,50 @@ impl Display for KillSwitch {
     }
 }
 
+/// Kill reason recorded when a switch set up with [`KillSwitch::kill_when_orphaned()`] is flipped
+/// because its last [`KillSwitch`] handle was dropped. Retrieve it from
+/// [`KillSwitchWatcher::reason()`] with `downcast_ref::<Orphaned>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Orphaned;
+
+impl Error for Orphaned {}
+
+impl Display for Orphaned {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "every kill switch handle was dropped")
+    }
+}
+
+/// Proof that a particular call to [`KillSwitch::kill()`] or [`KillSwitch::kill_with()`] was the
+/// one which flipped the switch. Only a single token is ever produced for each kill switch, so
+/// cleanup which must run exactly once can be tied to holding it.
+#[derive(Debug)]
+pub struct KillToken {
+    _private: (),
+}
+
 /// General error type for a [`KillSwitch`]
 #[derive(Debug, Clone)]
 pub enum KillSwitchErr {
-    /// Kill switch has already been flipped
-    AlreadyKilled,
+    /// Kill switch has already been flipped, carrying the reason given when it was flipped (if
+    /// any)
+    AlreadyKilled(Option<KillReason>),
 }
 
-impl std::error::Error for KillSwitchErr {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        None
+impl core::error::Error for KillSwitchErr {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            KillSwitchErr::AlreadyKilled(reason) => reason
+                .as_deref()
+                .map(|r| r as &(dyn core::error::Error + 'static)),
+        }
     }
 }
 
-impl std::fmt::Display for KillSwitchErr {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for KillSwitchErr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
-            KillSwitchErr::AlreadyKilled => write!(f, "kill switch already killed"),
+            KillSwitchErr::AlreadyKilled(_) => write!(f, "kill switch already killed"),
         }
     }
 }
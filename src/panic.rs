@@ -0,0 +1,136 @@
+#[cfg(not(loom))]
+use std::time::Instant;
+use std::{any::Any, error::Error, fmt::Display, panic::UnwindSafe, sync::Arc, thread};
+
+#[cfg(not(loom))]
+use crate::timer;
+use crate::{KillReason, KillSwitch};
+
+/// Kill reason recorded when a kill switch is flipped because a thread panicked, by
+/// [`KillSwitch::catch_unwind()`] or a [`KillOnDrop`] guard dropped during unwinding. Retrieve it
+/// from [`KillSwitchWatcher::reason()`](crate::KillSwitchWatcher::reason) with
+/// `downcast_ref::<Panicked>()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panicked {
+    /// The panic message, if the payload was a string and is known
+    pub message: Option<String>,
+    /// Name of the thread which panicked, if it has one
+    pub thread: Option<String>,
+    /// Source location of the panic, as `file:line:column`, if known
+    pub location: Option<String>,
+}
+
+impl Panicked {
+    fn current_thread(message: Option<String>) -> Self {
+        Self {
+            message,
+            thread: thread::current().name().map(String::from),
+            location: None,
+        }
+    }
+}
+
+impl Error for Panicked {}
+
+impl Display for Panicked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.thread {
+            Some(thread) => write!(f, "thread '{thread}' panicked")?,
+            None => write!(f, "thread panicked")?,
+        }
+        if let Some(location) = &self.location {
+            write!(f, " at {location}")?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Extract the message from a panic payload, which is a `&str` or `String` for any panic raised
+/// with a message
+fn payload_message(payload: &(dyn Any + Send)) -> Option<String> {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+}
+
+/// Guard returned by [`KillSwitch::guard()`], which flips the kill switch when it goes out of
+/// scope. This includes being dropped while the thread unwinds from a panic, in which case the
+/// switch is flipped with a [`Panicked`] reason.
+#[derive(Debug)]
+#[must_use = "dropping a KillOnDrop immediately flips the kill switch"]
+pub struct KillOnDrop {
+    kill: Option<KillSwitch>,
+}
+
+impl KillOnDrop {
+    /// Defuse the guard, so that dropping it no longer flips the kill switch
+    pub fn disarm(mut self) {
+        self.kill = None;
+    }
+}
+
+impl Drop for KillOnDrop {
+    fn drop(&mut self) {
+        if let Some(kill) = self.kill.take() {
+            let reason = match thread::panicking() {
+                true => Some(Arc::new(Panicked::current_thread(None)) as KillReason),
+                false => None,
+            };
+            let _ = kill.inner.kill(reason);
+        }
+    }
+}
+
+impl KillSwitch {
+    /// Produce a guard which flips the kill switch when it is dropped, including when the current
+    /// thread unwinds from a panic. Holding one in each worker thread means that any worker
+    /// exiting, for whatever reason, takes the rest of the group down with it
+    pub fn guard(&self) -> KillOnDrop {
+        KillOnDrop {
+            kill: Some(self.clone()),
+        }
+    }
+
+    /// Run `f`, flipping the kill switch if it panics. The panic message is recorded as a
+    /// [`Panicked`] reason, and the panic payload is returned as with
+    /// [`std::panic::catch_unwind()`]
+    pub fn catch_unwind<R>(&self, f: impl FnOnce() -> R + UnwindSafe) -> thread::Result<R> {
+        std::panic::catch_unwind(f).inspect_err(|payload| {
+            let reason = Panicked::current_thread(payload_message(payload.as_ref()));
+            let _ = self.inner.kill(Some(Arc::new(reason)));
+        })
+    }
+
+    /// Install a process-wide panic hook which flips this kill switch on the first panic in any
+    /// thread, recording the thread name, panic message and location as a [`Panicked`] reason.
+    ///
+    /// The previously installed hook (by default, the one which prints the panic message) is still
+    /// called for every panic. Installing several hooks chains them, so each switch is flipped
+    ///
+    /// A panic inside the hook would abort the process, so the hook only flips the switch and
+    /// wakes threads blocked in `wait()`. Waking tasks awaiting `killed()`, running `on_kill()`
+    /// callbacks and killing children is handed to a background thread, so may still be under
+    /// way after the panicking thread has finished unwinding.
+    #[cfg(not(loom))]
+    pub fn kill_on_panic(&self) {
+        let kill = self.watcher();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if kill.is_alive() {
+                let reason = Panicked {
+                    message: payload_message(info.payload()),
+                    thread: thread::current().name().map(String::from),
+                    location: info.location().map(|l| l.to_string()),
+                };
+                if let Ok(pending) = kill.inner.kill_deferred(Some(Arc::new(reason))) {
+                    timer::schedule(Instant::now(), move || pending.run());
+                }
+            }
+            previous(info);
+        }));
+    }
+}
@@ -0,0 +1,45 @@
+use alloc::boxed::Box;
+use std::thread;
+
+use crate::{CallbackHandle, KillSwitchWatcher};
+
+/// Keeps a thread registered to be unparked when the kill switch is flipped, until dropped.
+/// Obtained from [`KillSwitchWatcher::register_current_thread()`].
+#[derive(Debug)]
+#[must_use = "the thread is only unparked on kill while the registration is held"]
+pub struct ParkRegistration {
+    _handle: CallbackHandle,
+}
+
+impl KillSwitchWatcher {
+    /// Arrange for the current thread to be [unparked](thread::Thread::unpark) when the kill
+    /// switch is flipped, so that a thread blocked in [`thread::park()`] or
+    /// [`thread::park_timeout()`] notices the kill straight away. Parking can wake spuriously, so
+    /// check `is_alive()` after every park as usual.
+    ///
+    /// If the switch has already been flipped, the thread's unpark token is set immediately, so
+    /// its next park returns at once.
+    ///
+    /// ```
+    /// use killswitch_std::KillSwitch;
+    /// use std::thread;
+    ///
+    /// let kill = KillSwitch::default();
+    /// let w = kill.watcher();
+    /// let worker = thread::spawn(move || {
+    ///     let _registration = w.register_current_thread();
+    ///     while w.is_alive() {
+    ///         thread::park();
+    ///     }
+    /// });
+    ///
+    /// kill.kill().unwrap();
+    /// worker.join().unwrap();
+    /// ```
+    pub fn register_current_thread(&self) -> ParkRegistration {
+        let thread = thread::current();
+        ParkRegistration {
+            _handle: self.inner.on_kill(Box::new(move || thread.unpark())),
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use alloc::{boxed::Box, collections::VecDeque, sync::Arc};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+use crate::{CallbackHandle, KillSwitchWatcher};
+
+/// What happens to the items left in a [`KillableQueue`] when its kill switch is flipped
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DrainMode {
+    /// Consumers keep receiving the remaining items, and only see `None` once the queue is empty
+    #[default]
+    Drain,
+    /// The remaining items are dropped as soon as the switch is flipped
+    Discard,
+}
+
+/// A bounded multi-producer, multi-consumer queue which shuts down along with a kill switch.
+///
+/// Once the switch is flipped [`push()`](Self::push) hands the item back instead of queueing it,
+/// and [`pop()`](Self::pop) returns `None` once there is nothing left to hand out. Whether that
+/// includes the items queued before the kill depends on the [`DrainMode`]. Clones share the same
+/// queue.
+///
+/// ```
+/// use killswitch_std::{DrainMode, KillSwitch, KillableQueue};
+/// use std::thread;
+///
+/// let kill = KillSwitch::default();
+/// let queue = KillableQueue::new(kill.watcher(), 16, DrainMode::Drain);
+///
+/// let consumer = {
+///     let queue = queue.clone();
+///     thread::spawn(move || {
+///         let mut total = 0;
+///         while let Some(n) = queue.pop() {
+///             total += n;
+///         }
+///         total
+///     })
+/// };
+///
+/// for n in 1..=10 {
+///     queue.push(n).unwrap();
+/// }
+/// kill.kill().unwrap();
+/// assert_eq!(queue.push(11), Err(11));
+/// assert_eq!(consumer.join().unwrap(), 55);
+/// ```
+#[derive(Debug)]
+pub struct KillableQueue<T> {
+    shared: Arc<Shared<T>>,
+    watcher: KillSwitchWatcher,
+    /// Shared between clones, so that the kill callback is unregistered once the last clone is
+    /// dropped
+    _on_kill: Arc<CallbackHandle>,
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    capacity: usize,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    items: VecDeque<T>,
+    mode: DrainMode,
+}
+
+impl<T> Shared<T> {
+    /// Nothing in [`State`] can be left half-updated by a panic, so a poisoned lock can safely be
+    /// recovered.
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Send + 'static> KillableQueue<T> {
+    /// Create an empty queue holding at most `capacity` items, which shuts down when the switch
+    /// behind `watcher` is flipped.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is 0
+    pub fn new(watcher: KillSwitchWatcher, capacity: usize, mode: DrainMode) -> Self {
+        assert!(capacity > 0, "queue capacity must be at least 1");
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                items: VecDeque::with_capacity(capacity),
+                mode,
+            }),
+            capacity,
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+        });
+
+        let weak = Arc::downgrade(&shared);
+        let on_kill = watcher.inner.on_kill(Box::new(move || {
+            if let Some(shared) = weak.upgrade() {
+                // Taking the lock means every blocked thread is already waiting, and every other
+                // thread will see the switch as killed
+                let discarded = {
+                    let mut state = shared.lock();
+                    shared.not_empty.notify_all();
+                    shared.not_full.notify_all();
+                    match state.mode {
+                        DrainMode::Drain => VecDeque::new(),
+                        DrainMode::Discard => core::mem::take(&mut state.items),
+                    }
+                };
+                drop(discarded);
+            }
+        }));
+
+        Self {
+            shared,
+            watcher,
+            _on_kill: Arc::new(on_kill),
+        }
+    }
+}
+
+impl<T> KillableQueue<T> {
+    /// Add `item` to the back of the queue, blocking while it is full. Returns the item back if
+    /// the kill switch has been flipped, including while waiting for space
+    pub fn push(&self, item: T) -> Result<(), T> {
+        let mut state = self.shared.lock();
+        loop {
+            if !self.watcher.is_alive() {
+                return Err(item);
+            }
+            if state.items.len() < self.shared.capacity {
+                state.items.push_back(item);
+                self.shared.not_empty.notify_one();
+                return Ok(());
+            }
+            state = self
+                .shared
+                .not_full
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Take the item at the front of the queue, blocking while it is empty. Returns `None` once
+    /// the kill switch has been flipped and there are no items left to hand out
+    pub fn pop(&self) -> Option<T> {
+        let mut state = self.shared.lock();
+        loop {
+            // The kill callback may not have run yet, so discard here too rather than hand out
+            // items from a dead queue
+            if state.mode == DrainMode::Discard && !self.watcher.is_alive() {
+                let discarded = core::mem::take(&mut state.items);
+                drop(state);
+                drop(discarded);
+                return None;
+            }
+            if let Some(item) = state.items.pop_front() {
+                self.shared.not_full.notify_one();
+                return Some(item);
+            }
+            if !self.watcher.is_alive() {
+                return None;
+            }
+            state = self
+                .shared
+                .not_empty
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Change what happens to the remaining items on kill. Switching to [`DrainMode::Discard`]
+    /// after the switch has been flipped drops the remaining items straight away
+    pub fn set_drain_mode(&self, mode: DrainMode) {
+        let discarded = {
+            let mut state = self.shared.lock();
+            state.mode = mode;
+            match (mode, self.watcher.is_alive()) {
+                (DrainMode::Discard, false) => core::mem::take(&mut state.items),
+                _ => VecDeque::new(),
+            }
+        };
+        drop(discarded);
+    }
+
+    /// Number of items currently queued
+    pub fn len(&self) -> usize {
+        self.shared.lock().items.len()
+    }
+
+    /// Check whether there are no items queued
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of items the queue holds before `push()` blocks
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+}
+
+impl<T> Clone for KillableQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            watcher: self.watcher.clone(),
+            _on_kill: self._on_kill.clone(),
+        }
+    }
+}
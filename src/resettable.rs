@@ -0,0 +1,137 @@
+use alloc::{boxed::Box, sync::Arc};
+use core::{error::Error, fmt::Display};
+
+use crate::{
+    sync::{self, Mutex},
+    Inner, KillReason, KillSwitch, KillSwitchErr, KillSwitchWatcher, KillToken,
+};
+
+/// A kill switch which can be rearmed after being flipped, for subsystems which are restarted in
+/// place. Each [`reset()`](Self::reset) starts a new generation backed by a fresh switch, so
+/// components can keep hold of the `ResettableKillSwitch` itself instead of being handed a new
+/// switch on every restart.
+///
+/// Watchers and switches handed out are bound to the generation that was current at the time:
+/// once that generation is killed they stay killed, even after a reset. Compare
+/// [`KillSwitchWatcher::generation()`] against [`generation()`](Self::generation) to detect a
+/// stale one.
+///
+/// ```
+/// use killswitch_std::ResettableKillSwitch;
+///
+/// let kill = ResettableKillSwitch::new();
+/// let old = kill.watcher();
+///
+/// kill.kill().unwrap();
+/// assert_eq!(kill.reset(), 1);
+///
+/// assert!(kill.is_alive());
+/// assert!(!old.is_alive());
+/// assert_ne!(old.generation(), kill.generation());
+/// ```
+#[derive(Debug)]
+pub struct ResettableKillSwitch {
+    current: Mutex<KillSwitch>,
+}
+
+impl ResettableKillSwitch {
+    /// Create a new, alive, kill switch in generation 0
+    pub fn new() -> Self {
+        Self {
+            current: Mutex::new(KillSwitch::default()),
+        }
+    }
+
+    /// The switch for the current generation. It is an ordinary [`KillSwitch`], which stays bound
+    /// to this generation after a reset
+    pub fn current(&self) -> KillSwitch {
+        sync::lock(&self.current).clone()
+    }
+
+    /// The current generation, starting at 0 and incremented by every `reset()`
+    pub fn generation(&self) -> u64 {
+        sync::lock(&self.current).inner.generation
+    }
+
+    /// Start a new generation, returning its number. If the current generation has not been
+    /// killed yet, it is killed first with a [`Superseded`] reason, so nothing bound to it is left
+    /// running alongside the new generation.
+    pub fn reset(&self) -> u64 {
+        let mut inner = Inner::new();
+        let old = {
+            let mut current = sync::lock(&self.current);
+            inner.generation = current.inner.generation + 1;
+            core::mem::replace(&mut *current, KillSwitch::from_inner(Arc::new(inner)))
+        };
+        let generation = old.inner.generation + 1;
+        let _ = old.inner.kill(Some(Arc::new(Superseded { generation })));
+        generation
+    }
+
+    /// Check if the current generation has been flipped
+    pub fn is_alive(&self) -> bool {
+        sync::lock(&self.current).is_alive()
+    }
+
+    /// Flip the current generation, as with [`KillSwitch::kill()`]
+    pub fn kill(&self) -> Result<KillToken, KillSwitchErr> {
+        self.current().kill()
+    }
+
+    /// Flip the current generation, recording `reason` as the cause, as with
+    /// [`KillSwitch::kill_with()`]
+    pub fn kill_with(
+        &self,
+        reason: impl Into<Box<dyn Error + Send + Sync + 'static>>,
+    ) -> Result<KillToken, KillSwitchErr> {
+        self.current().kill_with(reason)
+    }
+
+    /// The reason the current generation was flipped, if one was given
+    pub fn reason(&self) -> Option<KillReason> {
+        sync::lock(&self.current).reason()
+    }
+
+    /// Produce a watcher bound to the current generation
+    pub fn watcher(&self) -> KillSwitchWatcher {
+        sync::lock(&self.current).watcher()
+    }
+}
+
+impl Default for ResettableKillSwitch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for ResettableKillSwitch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} (generation {})", self.current(), self.generation())
+    }
+}
+
+impl KillSwitchWatcher {
+    /// The generation of the switch this watcher is bound to. This is 0 unless it came from a
+    /// [`ResettableKillSwitch`], in which case it is the generation that was current when the
+    /// watcher was created
+    pub fn generation(&self) -> u64 {
+        self.inner.generation
+    }
+}
+
+/// Kill reason recorded when [`ResettableKillSwitch::reset()`] replaces a generation which was
+/// still alive. Retrieve it from [`KillSwitchWatcher::reason()`] with
+/// `downcast_ref::<Superseded>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Superseded {
+    /// The generation which replaced the killed one
+    pub generation: u64,
+}
+
+impl Error for Superseded {}
+
+impl Display for Superseded {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "superseded by generation {}", self.generation)
+    }
+}
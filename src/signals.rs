@@ -0,0 +1,386 @@
+//! Flipping kill switches from Unix signals, using the self-pipe trick: the signal handler only
+//! writes the signal number to a pipe, which is async-signal-safe, and a background thread reads
+//! the pipe and does the actual killing.
+
+use std::{
+    error::Error,
+    ffi::c_int,
+    fmt::Display,
+    fs::File,
+    io::{self, Read},
+    os::fd::FromRawFd,
+    panic::{catch_unwind, AssertUnwindSafe},
+    process,
+    sync::{
+        atomic::{AtomicBool, AtomicI32, Ordering::Relaxed},
+        Arc, Mutex, MutexGuard, PoisonError, Weak,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{timer, Inner, KillSwitch};
+
+/// Hangup, conventionally sent when the controlling terminal closes or to request a reload
+pub const SIGHUP: i32 = 1;
+/// Interrupt, sent by Ctrl-C in a terminal
+pub const SIGINT: i32 = 2;
+/// Termination request, sent by default by `kill` and by most service managers
+pub const SIGTERM: i32 = 15;
+
+/// Kill reason recorded when a kill switch is flipped by a signal registered with
+/// [`KillSwitch::kill_on_signals()`]. Retrieve it from
+/// [`KillSwitchWatcher::reason()`](crate::KillSwitchWatcher::reason) with
+/// `downcast_ref::<Signalled>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signalled {
+    /// The number of the signal which was received
+    pub signal: i32,
+}
+
+impl Error for Signalled {}
+
+impl Display for Signalled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.signal {
+            SIGHUP => write!(f, "received SIGHUP"),
+            SIGINT => write!(f, "received SIGINT"),
+            SIGTERM => write!(f, "received SIGTERM"),
+            signal => write!(f, "received signal {signal}"),
+        }
+    }
+}
+
+/// What to do when a graceful shutdown started by a signal takes too long, for use with
+/// [`KillSwitch::kill_on_signals_with()`].
+///
+/// The first signal flips the kill switch as usual. Escalation happens when another registered
+/// signal arrives while the switch is already killed, or once the [grace period](Self::grace) after
+/// the first signal has run out. By default it exits the process with status `128 + signal`, the
+/// shell convention for termination by a signal; a [hook](Self::hook) replaces that. Escalation
+/// happens at most once.
+///
+/// ```no_run
+/// use killswitch_std::{Escalation, KillSwitch, SIGINT, SIGTERM};
+/// use std::time::Duration;
+///
+/// let kill = KillSwitch::default();
+/// kill.kill_on_signals_with(
+///     &[SIGINT, SIGTERM],
+///     Escalation::new().grace(Duration::from_secs(30)),
+/// )
+/// .unwrap();
+/// ```
+#[derive(Default)]
+pub struct Escalation {
+    grace: Option<Duration>,
+    exit_code: Option<i32>,
+    hook: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl Escalation {
+    /// Escalate on a second signal only, exiting with status `128 + signal`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also escalate if the process is still running `grace` after the first signal
+    pub fn grace(mut self, grace: Duration) -> Self {
+        self.grace = Some(grace);
+        self
+    }
+
+    /// Exit with `code` instead of `128 + signal`
+    pub fn exit_code(mut self, code: i32) -> Self {
+        self.exit_code = Some(code);
+        self
+    }
+
+    /// Call `hook` instead of exiting the process. It runs on a background thread, and a panic in
+    /// it is caught and ignored.
+    pub fn hook(mut self, hook: impl FnOnce() + Send + 'static) -> Self {
+        self.hook = Some(Box::new(hook));
+        self
+    }
+}
+
+impl std::fmt::Debug for Escalation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Escalation")
+            .field("grace", &self.grace)
+            .field("exit_code", &self.exit_code)
+            .field("hook", &self.hook.is_some())
+            .finish()
+    }
+}
+
+/// Shared state of an [`Escalation`] once registered
+struct Escalator {
+    grace: Option<Duration>,
+    exit_code: Option<i32>,
+    hook: Mutex<Option<Box<dyn FnOnce() + Send>>>,
+    escalated: AtomicBool,
+}
+
+impl Escalator {
+    fn escalate(&self, signal: c_int) {
+        if self.escalated.swap(true, Relaxed) {
+            return;
+        }
+        let hook = self
+            .hook
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        match hook {
+            Some(hook) => {
+                let _ = catch_unwind(AssertUnwindSafe(hook));
+            }
+            None => process::exit(self.exit_code.unwrap_or(128 + signal)),
+        }
+    }
+}
+
+/// Raw bindings to libc. The flag values follow the generic Linux ABI shared by x86, Arm and
+/// RISC-V, and differ on some other architectures (for example MIPS), which is why the module is
+/// only built for those listed in `lib.rs`.
+mod sys {
+    use std::ffi::{c_int, c_void};
+
+    /// `sighandler_t`, which is either a handler function or one of the special values below
+    pub(super) type SigHandler = usize;
+
+    pub(super) const SIG_DFL: SigHandler = 0;
+    pub(super) const SIG_ERR: SigHandler = usize::MAX;
+    pub(super) const F_GETFL: c_int = 3;
+    pub(super) const F_SETFL: c_int = 4;
+    pub(super) const O_NONBLOCK: c_int = 0o4000;
+    pub(super) const O_CLOEXEC: c_int = 0o2000000;
+
+    extern "C" {
+        pub(super) fn signal(signum: c_int, handler: SigHandler) -> SigHandler;
+        pub(super) fn raise(signum: c_int) -> c_int;
+        pub(super) fn pipe2(fds: *mut c_int, flags: c_int) -> c_int;
+        pub(super) fn fcntl(fd: c_int, cmd: c_int, ...) -> c_int;
+        pub(super) fn write(fd: c_int, buf: *const c_void, count: usize) -> isize;
+        pub(super) fn __errno_location() -> *mut c_int;
+    }
+}
+
+/// Write end of the self-pipe, read by the signal handler
+static PIPE: AtomicI32 = AtomicI32::new(-1);
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    started: false,
+    installed: Vec::new(),
+    switches: Vec::new(),
+});
+
+struct Registry {
+    /// Whether the pipe has been created and the listener thread started
+    started: bool,
+    /// Signals which already have the handler installed, with the handler each one had before
+    installed: Vec<(c_int, sys::SigHandler)>,
+    switches: Vec<Registration>,
+}
+
+impl Registry {
+    /// Forget registrations whose switch has been dropped
+    fn prune(&mut self) {
+        self.switches.retain(|r| r.switch.strong_count() > 0);
+    }
+}
+
+struct Registration {
+    signals: Vec<c_int>,
+    switch: Weak<Inner>,
+    escalation: Option<Arc<Escalator>>,
+}
+
+/// Only async-signal-safe functions may be called here, so all it does is forward the signal
+/// number down the pipe. The write end is non-blocking, so if the pipe is somehow full the signal
+/// is dropped rather than deadlocking the interrupted thread.
+extern "C" fn handler(signal: c_int) {
+    // SAFETY: `write` and `__errno_location` are async-signal-safe, and errno is restored so
+    // that the interrupted code does not observe a change
+    unsafe {
+        let errno = sys::__errno_location();
+        let saved = *errno;
+        let byte = signal as u8;
+        sys::write(PIPE.load(Relaxed), (&byte as *const u8).cast(), 1);
+        *errno = saved;
+    }
+}
+
+/// Nothing in [`Registry`] can be left half-updated by a panic, so a poisoned lock can safely be
+/// recovered.
+fn lock() -> MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Create the pipe and start the listener thread which reads from it
+fn start() -> io::Result<()> {
+    let mut fds = [-1; 2];
+    // SAFETY: `fds` has room for the two descriptors written by `pipe2`
+    if unsafe { sys::pipe2(fds.as_mut_ptr(), sys::O_CLOEXEC) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let [read_fd, write_fd] = fds;
+    // SAFETY: `read_fd` was just created and is owned by nothing else
+    let mut reader = unsafe { File::from_raw_fd(read_fd) };
+    // SAFETY: plain fcntl calls on a descriptor we own
+    unsafe {
+        let flags = sys::fcntl(write_fd, sys::F_GETFL);
+        if flags < 0 || sys::fcntl(write_fd, sys::F_SETFL, flags | sys::O_NONBLOCK) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    PIPE.store(write_fd, Relaxed);
+
+    thread::Builder::new()
+        .name("killswitch-signals".into())
+        .spawn(move || {
+            let mut byte = [0u8];
+            loop {
+                match reader.read(&mut byte) {
+                    // A panic must not stop the thread, or every later signal would be lost
+                    Ok(1) => {
+                        let _ = catch_unwind(|| deliver(c_int::from(byte[0])));
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    // The write end is never closed, so anything else is unrecoverable
+                    _ => return,
+                }
+            }
+        })?;
+    Ok(())
+}
+
+/// Runs on the listener thread: kill every switch registered for `signal`, and escalate for those
+/// which were already killed
+fn deliver(signal: c_int) {
+    let switches: Vec<_> = {
+        let mut registry = lock();
+        registry.prune();
+        let switches: Vec<_> = registry
+            .switches
+            .iter()
+            .filter(|r| r.signals.contains(&signal))
+            .filter_map(|r| Some((r.switch.upgrade()?, r.escalation.clone())))
+            .collect();
+        if switches.is_empty() {
+            // Every switch registered for the signal is gone, so put back the handler it had
+            // before, as if this one had never been installed, and let the default action take
+            // its course. The lock is still held, so no new registration can reinstall the
+            // handler in between.
+            let Some(index) = registry.installed.iter().position(|&(s, _)| s == signal) else {
+                return;
+            };
+            let (_, previous) = registry.installed.swap_remove(index);
+            // SAFETY: `previous` is exactly what was installed before the first registration, and
+            // raising a signal with its default action is always sound
+            unsafe {
+                sys::signal(signal, previous);
+                if previous == sys::SIG_DFL {
+                    sys::raise(signal);
+                }
+            }
+            return;
+        }
+        switches
+    };
+
+    let reason = Arc::new(Signalled { signal });
+    for (inner, escalation) in switches {
+        let killed = inner.kill(Some(reason.clone())).is_ok();
+        let Some(escalation) = escalation else {
+            continue;
+        };
+        if !killed {
+            escalation.escalate(signal);
+        } else if let Some(grace) = escalation.grace {
+            // A grace period too long to represent will never run out
+            if let Some(at) = Instant::now().checked_add(grace) {
+                timer::schedule(at, move || escalation.escalate(signal));
+            }
+        }
+    }
+}
+
+/// Install the handler for `signals` and register `switch` to be killed by them
+fn register(
+    signals: &[i32],
+    switch: Weak<Inner>,
+    escalation: Option<Arc<Escalator>>,
+) -> io::Result<()> {
+    let mut registry = lock();
+    if !registry.started {
+        start()?;
+        registry.started = true;
+    }
+    registry.prune();
+
+    // Previous handlers of the signals installed by this call, put back if a later one fails
+    let mut replaced = Vec::new();
+    for &signal in signals {
+        let installed = registry.installed.iter().chain(&replaced);
+        if installed.clone().any(|&(s, _)| s == signal) {
+            continue;
+        }
+        // SAFETY: `handler` only performs async-signal-safe operations
+        let previous = unsafe { sys::signal(signal, handler as extern "C" fn(c_int) as usize) };
+        if previous == sys::SIG_ERR {
+            let error = io::Error::last_os_error();
+            for (signal, previous) in replaced {
+                // SAFETY: `previous` is exactly what was installed before this call
+                unsafe { sys::signal(signal, previous) };
+            }
+            return Err(error);
+        }
+        replaced.push((signal, previous));
+    }
+    registry.installed.extend(replaced);
+    registry.switches.push(Registration {
+        signals: signals.to_vec(),
+        switch,
+        escalation,
+    });
+    Ok(())
+}
+
+impl KillSwitch {
+    /// Flip the kill switch when the process receives any of `signals` (for example [`SIGINT`]
+    /// and [`SIGTERM`]), with a [`Signalled`] reason recording which one arrived.
+    ///
+    /// This replaces any existing handler for those signals. The handler itself only writes to a
+    /// pipe, and a single background thread, started the first time this is called, flips every
+    /// kill switch registered for the signal. Registrations only hold a weak reference to the
+    /// switch, so do not keep it alive. If a signal arrives once every switch registered for it
+    /// has been dropped, the handler it had before the first registration is put back. If that
+    /// was the default action, the signal is raised again, so for example Ctrl-C goes back to
+    /// terminating the process, while a signal which was ignored (such as SIGHUP under `nohup`)
+    /// stays ignored.
+    ///
+    /// Returns an error if a handler cannot be installed, for example for `SIGKILL`, in which case
+    /// none of `signals` are changed
+    pub fn kill_on_signals(&self, signals: &[i32]) -> io::Result<()> {
+        register(signals, Arc::downgrade(&self.inner), None)
+    }
+
+    /// Like [`kill_on_signals()`](Self::kill_on_signals), but if shutting down takes too long
+    /// `escalation` forces the issue: a second signal, or the end of its grace period, exits the
+    /// process or calls its hook. See [`Escalation`].
+    pub fn kill_on_signals_with(&self, signals: &[i32], escalation: Escalation) -> io::Result<()> {
+        let escalator = Escalator {
+            grace: escalation.grace,
+            exit_code: escalation.exit_code,
+            hook: Mutex::new(escalation.hook),
+            escalated: AtomicBool::new(false),
+        };
+        register(
+            signals,
+            Arc::downgrade(&self.inner),
+            Some(Arc::new(escalator)),
+        )
+    }
+}
@@ -0,0 +1,110 @@
+use alloc::boxed::Box;
+use core::{error::Error, fmt::Display};
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+use crate::{
+    Inner, InnerRef, KillReason, KillSwitchErr, KillSwitchWatcher, KillToken, KilledFuture,
+};
+
+/// A kill switch which can be created in a `const` context, for use as a process-wide shutdown
+/// flag without any lazy initialisation or allocation:
+///
+/// ```
+/// use killswitch_std::StaticKillSwitch;
+///
+/// static SHUTDOWN: StaticKillSwitch = StaticKillSwitch::new();
+///
+/// let watcher = SHUTDOWN.watcher();
+/// assert!(watcher.is_alive());
+/// SHUTDOWN.kill().unwrap();
+/// assert!(!watcher.is_alive());
+/// ```
+///
+/// The watchers it hands out are ordinary [`KillSwitchWatcher`]s, so can be passed to anything
+/// expecting one, or combined with other watchers through [`KillSwitchWatcher::any_of()`].
+#[derive(Debug)]
+pub struct StaticKillSwitch {
+    inner: Inner,
+}
+
+impl StaticKillSwitch {
+    /// Create a new, alive, kill switch
+    pub const fn new() -> Self {
+        Self {
+            inner: Inner::new(),
+        }
+    }
+
+    /// Check if the kill switch has been flipped. Before flipping will return `true`, and
+    /// afterwards will return `false`
+    pub fn is_alive(&self) -> bool {
+        self.inner.is_alive()
+    }
+
+    /// Block the current thread until the kill switch has been flipped. Returns immediately if it
+    /// has already been flipped.
+    #[cfg(feature = "std")]
+    pub fn wait(&self) {
+        self.inner.wait()
+    }
+
+    /// Block the current thread until the kill switch has been flipped, or until `timeout` has
+    /// elapsed. Returns `true` if the switch has been flipped, and `false` if the wait timed out
+    #[cfg(feature = "std")]
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        self.inner.wait_timeout(timeout)
+    }
+
+    /// Produce a future which resolves once the kill switch has been flipped. The future only
+    /// relies on the standard library, so can be awaited from any async runtime.
+    pub fn killed(&'static self) -> KilledFuture {
+        KilledFuture::new(InnerRef::Static(&self.inner))
+    }
+
+    /// Flip the kill switch, with the same semantics as [`KillSwitch::kill()`](crate::KillSwitch::kill)
+    pub fn kill(&self) -> Result<KillToken, KillSwitchErr> {
+        self.inner.kill(None)
+    }
+
+    /// Flip the kill switch recording `reason` as the cause, with the same semantics as
+    /// [`KillSwitch::kill_with()`](crate::KillSwitch::kill_with)
+    pub fn kill_with(
+        &self,
+        reason: impl Into<Box<dyn Error + Send + Sync + 'static>>,
+    ) -> Result<KillToken, KillSwitchErr> {
+        self.inner.kill(Some(reason.into().into()))
+    }
+
+    /// The reason the kill switch was flipped, if one was given to `kill_with()`. Returns `None`
+    /// while the switch is alive, or if it was flipped with a plain `kill()`
+    pub fn reason(&self) -> Option<KillReason> {
+        self.inner.reason()
+    }
+
+    /// Produce a kill switch which can only watch the value, but cannot flip the switch
+    pub fn watcher(&'static self) -> KillSwitchWatcher {
+        KillSwitchWatcher {
+            inner: InnerRef::Static(&self.inner),
+        }
+    }
+}
+
+impl Default for StaticKillSwitch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for StaticKillSwitch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self.is_alive() {
+                true => "alive",
+                false => "killed",
+            }
+        )
+    }
+}
@@ -0,0 +1,114 @@
+//! Synchronisation primitives used for the shared state of a kill switch. When built with
+//! `RUSTFLAGS="--cfg loom"` these are swapped for the model-checked versions from
+//! [loom](https://docs.rs/loom), so that the tests in `tests/loom.rs` can explore every
+//! interleaving of kills and waits. Without the `std` feature there is no operating system to
+//! block on, so the state is guarded by a spin lock instead.
+
+#[cfg(loom)]
+pub(crate) use loom::sync::{
+    atomic::{fence, AtomicBool, AtomicU8, AtomicUsize},
+    Condvar, Mutex, MutexGuard,
+};
+#[cfg(all(not(loom), feature = "std"))]
+pub(crate) use std::sync::{
+    atomic::{fence, AtomicBool, AtomicU8, AtomicUsize},
+    Condvar, Mutex, MutexGuard,
+};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use core::sync::atomic::{fence, AtomicBool, AtomicU8, AtomicUsize};
+#[cfg(not(feature = "std"))]
+pub(crate) use spin::{Mutex, MutexGuard};
+
+/// Lock `mutex`, recovering the guard if a panic poisoned it. Every user of this keeps its data
+/// consistent across panics, so poisoning carries no meaning.
+#[cfg(feature = "std")]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Lock `mutex`, spinning until it is available
+#[cfg(not(feature = "std"))]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock()
+}
+
+#[cfg(not(feature = "std"))]
+mod spin {
+    use core::{
+        cell::UnsafeCell,
+        fmt::Debug,
+        ops::{Deref, DerefMut},
+        sync::atomic::{
+            AtomicBool,
+            Ordering::{Acquire, Relaxed, Release},
+        },
+    };
+
+    /// Minimal spin lock standing in for `std::sync::Mutex`. The critical sections guarded by it
+    /// only ever touch a few collections, so spinning is never held up for long.
+    pub(crate) struct Mutex<T> {
+        locked: AtomicBool,
+        data: UnsafeCell<T>,
+    }
+
+    // SAFETY: the data is only reachable through a guard, and the lock hands out at most one guard
+    // at a time
+    unsafe impl<T: Send> Sync for Mutex<T> {}
+
+    pub(crate) struct MutexGuard<'a, T> {
+        mutex: &'a Mutex<T>,
+    }
+
+    impl<T> Mutex<T> {
+        pub(crate) const fn new(data: T) -> Self {
+            Self {
+                locked: AtomicBool::new(false),
+                data: UnsafeCell::new(data),
+            }
+        }
+
+        pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+            while self
+                .locked
+                .compare_exchange_weak(false, true, Acquire, Relaxed)
+                .is_err()
+            {
+                while self.locked.load(Relaxed) {
+                    core::hint::spin_loop();
+                }
+            }
+            MutexGuard { mutex: self }
+        }
+    }
+
+    impl<T> Debug for Mutex<T> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("Mutex").finish_non_exhaustive()
+        }
+    }
+
+    impl<T> Deref for MutexGuard<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            // SAFETY: holding the guard means holding the lock
+            unsafe { &*self.mutex.data.get() }
+        }
+    }
+
+    impl<T> DerefMut for MutexGuard<'_, T> {
+        fn deref_mut(&mut self) -> &mut T {
+            // SAFETY: holding the guard means holding the lock
+            unsafe { &mut *self.mutex.data.get() }
+        }
+    }
+
+    impl<T> Drop for MutexGuard<'_, T> {
+        fn drop(&mut self) {
+            self.mutex.locked.store(false, Release);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! A single background thread, shared by every kill switch in the process, which runs tasks once
+//! their deadline has passed. The thread is only spawned the first time a task is scheduled.
+//!
+//! Cancelled tasks stay in the queue until they are pruned, which happens whenever the queue has
+//! doubled in size since the last prune, so it never holds more than about twice as many entries
+//! as there are live tasks, at an amortised cost of a constant number of entries visited per
+//! task scheduled.
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering::Relaxed},
+        Arc, Condvar, Mutex, MutexGuard, OnceLock, PoisonError,
+    },
+    thread,
+    time::Instant,
+};
+
+static TIMER: OnceLock<Timer> = OnceLock::new();
+
+type Task = Box<dyn FnOnce() + Send>;
+
+/// Never prune a queue smaller than this, as pruning tiny queues is not worth the effort
+const MIN_PRUNE_LEN: usize = 64;
+
+/// Run `task` on the timer thread once `at` has passed.
+pub(crate) fn schedule(at: Instant, task: impl FnOnce() + Send + 'static) {
+    push(at, None, Box::new(task));
+}
+
+/// Run `task` on the timer thread once `at` has passed, unless the returned handle has been
+/// dropped by then.
+pub(crate) fn schedule_cancellable(
+    at: Instant,
+    task: impl FnOnce() + Send + 'static,
+) -> TimerHandle {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    push(at, Some(cancelled.clone()), Box::new(task));
+    TimerHandle { cancelled }
+}
+
+/// Cancels its task when dropped. The task is removed from the queue the next time it is pruned.
+#[derive(Debug)]
+pub(crate) struct TimerHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Drop for TimerHandle {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Relaxed);
+    }
+}
+
+fn push(at: Instant, cancelled: Option<Arc<AtomicBool>>, task: Task) {
+    let mut created = false;
+    let timer = TIMER.get_or_init(|| {
+        created = true;
+        Timer::default()
+    });
+    if created {
+        thread::Builder::new()
+            .name("killswitch-timer".into())
+            .spawn(|| timer.run())
+            .expect("failed to spawn kill switch timer thread");
+    }
+
+    let mut state = timer.lock();
+    let seq = state.next_seq;
+    state.next_seq += 1;
+    let wake = state.queue.peek().is_none_or(|Reverse(next)| at < next.at);
+    state.queue.push(Reverse(Entry {
+        at,
+        seq,
+        cancelled,
+        task,
+    }));
+    let pruned = match state.queue.len() >= state.prune_len.max(MIN_PRUNE_LEN) {
+        true => state.prune(),
+        false => Vec::new(),
+    };
+    if wake {
+        timer.cvar.notify_one();
+    }
+    // The tasks may own anything, so are only dropped once the lock is released
+    drop(state);
+    drop(pruned);
+}
+
+#[derive(Default)]
+struct Timer {
+    state: Mutex<TimerState>,
+    cvar: Condvar,
+}
+
+#[derive(Default)]
+struct TimerState {
+    next_seq: u64,
+    queue: BinaryHeap<Reverse<Entry>>,
+    /// Queue length at which to prune cancelled entries next
+    prune_len: usize,
+}
+
+impl TimerState {
+    /// Remove every cancelled entry from the queue, returning them to be dropped
+    fn prune(&mut self) -> Vec<Reverse<Entry>> {
+        let (cancelled, live) = std::mem::take(&mut self.queue)
+            .into_vec()
+            .into_iter()
+            .partition(|Reverse(entry)| entry.is_cancelled());
+        self.queue = BinaryHeap::from(live);
+        self.prune_len = 2 * self.queue.len();
+        cancelled
+    }
+}
+
+/// Entries are ordered by deadline, with ties broken by the order in which they were scheduled.
+struct Entry {
+    at: Instant,
+    seq: u64,
+    /// Set for tasks scheduled with [`schedule_cancellable()`]
+    cancelled: Option<Arc<AtomicBool>>,
+    task: Task,
+}
+
+impl Entry {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.as_ref().is_some_and(|c| c.load(Relaxed))
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.at, self.seq).cmp(&(other.at, other.seq))
+    }
+}
+
+impl Timer {
+    /// Tasks never run while the lock is held, so a poisoned lock can safely be recovered.
+    fn lock(&self) -> MutexGuard<'_, TimerState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn run(&self) -> ! {
+        let mut state = self.lock();
+        loop {
+            let now = Instant::now();
+            match state.queue.peek() {
+                Some(Reverse(next)) if next.at <= now => {
+                    let Reverse(entry) = state.queue.pop().expect("peeked entry");
+                    drop(state);
+                    if !entry.is_cancelled() {
+                        let _ = catch_unwind(AssertUnwindSafe(entry.task));
+                    }
+                    state = self.lock();
+                }
+                Some(Reverse(next)) => {
+                    let timeout = next.at - now;
+                    state = self
+                        .cvar
+                        .wait_timeout(state, timeout)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0;
+                }
+                None => {
+                    state = self
+                        .cvar
+                        .wait(state)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+        }
+    }
+}
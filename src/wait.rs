@@ -0,0 +1,125 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    sync::PoisonError,
+    time::{Duration, Instant},
+};
+
+use crate::{Inner, KillReason, KillSwitch, KillSwitchWatcher, Level};
+
+impl Inner {
+    pub(crate) fn wait(&self) {
+        self.wait_until(None);
+    }
+
+    pub(crate) fn wait_timeout(&self, timeout: Duration) -> bool {
+        self.wait_until(Instant::now().checked_add(timeout))
+    }
+
+    /// Sleep until `deadline` (or forever, if there is none), returning early if the switch is
+    /// killed
+    fn sleep_until(&self, deadline: Option<Instant>) -> Result<(), Killed> {
+        match self.wait_until(deadline) {
+            true => Err(Killed {
+                reason: self.reason(),
+            }),
+            false => Ok(()),
+        }
+    }
+
+    /// Block until the switch is killed, or until `deadline` passes (if there is one). Returns
+    /// `true` if the switch has been killed.
+    fn wait_until(&self, deadline: Option<Instant>) -> bool {
+        self.wait_for_level(Level::KILLED, deadline)
+    }
+
+    /// Block until the switch reaches `level`, or until `deadline` passes (if there is one).
+    /// Returns `true` if the level has been reached.
+    pub(crate) fn wait_for_level(&self, level: Level, deadline: Option<Instant>) -> bool {
+        if self.level() >= level {
+            return true;
+        }
+        let mut guard = self.lock();
+        while self.level() < level {
+            guard = match deadline {
+                None => self
+                    .cvar
+                    .wait(guard)
+                    .unwrap_or_else(PoisonError::into_inner),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return false;
+                    }
+                    self.cvar
+                        .wait_timeout(guard, deadline - now)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+            };
+        }
+        true
+    }
+}
+
+impl KillSwitchWatcher {
+    /// Block the current thread until the kill switch has been flipped. Returns immediately if it
+    /// has already been flipped.
+    pub fn wait(&self) {
+        self.inner.wait()
+    }
+
+    /// Block the current thread until the kill switch has been flipped, or until `timeout` has
+    /// elapsed. Returns `true` if the switch has been flipped, and `false` if the wait timed out
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        self.inner.wait_timeout(timeout)
+    }
+
+    /// Sleep for `duration`, waking up as soon as the kill switch is flipped. Returns `Ok` if the
+    /// full duration elapsed, and [`Killed`] if the switch was flipped first (including before
+    /// the call)
+    pub fn sleep(&self, duration: Duration) -> Result<(), Killed> {
+        self.inner.sleep_until(Instant::now().checked_add(duration))
+    }
+
+    /// Sleep until `deadline`, waking up as soon as the kill switch is flipped. Returns `Ok` if
+    /// the deadline passed, and [`Killed`] if the switch was flipped first (including before the
+    /// call)
+    pub fn sleep_until(&self, deadline: Instant) -> Result<(), Killed> {
+        self.inner.sleep_until(Some(deadline))
+    }
+}
+
+impl KillSwitch {
+    /// Block the current thread until the kill switch has been flipped. Returns immediately if it
+    /// has already been flipped.
+    pub fn wait(&self) {
+        self.inner.wait()
+    }
+
+    /// Block the current thread until the kill switch has been flipped, or until `timeout` has
+    /// elapsed. Returns `true` if the switch has been flipped, and `false` if the wait timed out
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        self.inner.wait_timeout(timeout)
+    }
+}
+
+/// Error returned by [`KillSwitchWatcher::sleep()`] and [`KillSwitchWatcher::sleep_until()`] when
+/// the kill switch is flipped before the sleep is over
+#[derive(Debug, Clone)]
+pub struct Killed {
+    /// The reason the kill switch was flipped, if one was given
+    pub reason: Option<KillReason>,
+}
+
+impl Error for Killed {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.reason.as_deref().map(|r| r as &(dyn Error + 'static))
+    }
+}
+
+impl Display for Killed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "kill switch was flipped")
+    }
+}
@@ -0,0 +1,245 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicU64, Ordering::Relaxed},
+        Arc, Mutex, MutexGuard, PoisonError, Weak,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{timer, KillSwitch, KillSwitchWatcher};
+
+/// Kill reason recorded when a [`Watchdog`] flips its kill switch because one of its feeders went
+/// quiet. Retrieve it from [`KillSwitchWatcher::reason()`] with `downcast_ref::<MissedFeed>()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissedFeed {
+    /// Name of the feeder which missed its feed
+    pub feeder: String,
+    /// When the feeder was last fed (or registered, if it was never fed)
+    pub last_feed: Instant,
+}
+
+impl Error for MissedFeed {}
+
+impl Display for MissedFeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "watchdog feeder '{}' missed its feed", self.feeder)
+    }
+}
+
+/// A dead-man's switch built on a [`KillSwitch`]. Workers register a named [`Feeder`] and call
+/// [`Feeder::feed()`] periodically, and the kill switch is flipped (with a [`MissedFeed`] reason)
+/// as soon as any one feeder goes longer than the configured interval without being fed.
+///
+/// Feeders are checked by the same background timer thread which handles
+/// [`KillSwitch::kill_after()`], so a watchdog does not need a thread of its own. Dropping a
+/// [`Feeder`] stops it being watched, so a worker which finishes cleanly does not trip the
+/// watchdog.
+#[derive(Clone, Debug)]
+pub struct Watchdog {
+    shared: Arc<Shared>,
+}
+
+/// Registration of a single named worker with a [`Watchdog`]. Feeding is a single atomic store,
+/// so is cheap enough to call from hot loops.
+#[derive(Debug)]
+pub struct Feeder {
+    shared: Arc<Shared>,
+    slot: Arc<Slot>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    kill: KillSwitch,
+    interval: Duration,
+    /// Feed times are stored as nanoseconds since this instant, so that they fit in an atomic
+    epoch: Instant,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    feeders: Vec<Arc<Slot>>,
+    /// Whether a check is pending on the timer thread
+    scheduled: bool,
+}
+
+#[derive(Debug)]
+struct Slot {
+    name: String,
+    last_feed: AtomicU64,
+}
+
+impl Watchdog {
+    /// Create a watchdog with its own new kill switch, which is flipped if any feeder goes longer
+    /// than `interval` without being fed
+    pub fn new(interval: Duration) -> Self {
+        Self::with_switch(&KillSwitch::default(), interval)
+    }
+
+    /// Create a watchdog which flips `kill` if any feeder goes longer than `interval` without being
+    /// fed
+    pub fn with_switch(kill: &KillSwitch, interval: Duration) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                kill: kill.clone(),
+                interval,
+                epoch: Instant::now(),
+                state: Mutex::new(State::default()),
+            }),
+        }
+    }
+
+    /// Register a new feeder called `name`, which counts as fed at the moment it is registered
+    pub fn feeder(&self, name: impl Into<String>) -> Feeder {
+        let slot = Arc::new(Slot {
+            name: name.into(),
+            last_feed: AtomicU64::new(self.shared.now()),
+        });
+
+        let schedule = {
+            let mut state = self.shared.lock();
+            state.feeders.push(slot.clone());
+            !std::mem::replace(&mut state.scheduled, true)
+        };
+        // A new feeder always expires after every existing one, so a check only needs scheduling
+        // if none is already pending. An interval too long to represent never expires.
+        if let (true, Some(at)) = (schedule, Instant::now().checked_add(self.shared.interval)) {
+            Shared::schedule(&self.shared, at);
+        }
+
+        Feeder {
+            shared: self.shared.clone(),
+            slot,
+        }
+    }
+
+    /// When the feeder called `name` was last fed. If several feeders share a name, the least
+    /// recent feed is returned
+    pub fn last_feed(&self, name: &str) -> Option<Instant> {
+        self.shared
+            .lock()
+            .feeders
+            .iter()
+            .filter(|slot| slot.name == name)
+            .map(|slot| self.shared.last_feed(slot))
+            .min()
+    }
+
+    /// The name of every registered feeder, along with when it was last fed
+    pub fn last_feeds(&self) -> Vec<(String, Instant)> {
+        self.shared
+            .lock()
+            .feeders
+            .iter()
+            .map(|slot| (slot.name.clone(), self.shared.last_feed(slot)))
+            .collect()
+    }
+
+    /// The interval within which every feeder must be fed
+    pub fn interval(&self) -> Duration {
+        self.shared.interval
+    }
+
+    /// The kill switch flipped by this watchdog
+    pub fn kill_switch(&self) -> &KillSwitch {
+        &self.shared.kill
+    }
+
+    /// Produce a watcher for the kill switch flipped by this watchdog
+    pub fn watcher(&self) -> KillSwitchWatcher {
+        self.shared.kill.watcher()
+    }
+}
+
+impl Feeder {
+    /// Record that this worker is still making progress
+    pub fn feed(&self) {
+        self.slot.last_feed.store(self.shared.now(), Relaxed);
+    }
+
+    /// When this feeder was last fed
+    pub fn last_feed(&self) -> Instant {
+        self.shared.last_feed(&self.slot)
+    }
+
+    /// The name this feeder was registered with
+    pub fn name(&self) -> &str {
+        &self.slot.name
+    }
+}
+
+impl Drop for Feeder {
+    fn drop(&mut self) {
+        self.shared
+            .lock()
+            .feeders
+            .retain(|slot| !Arc::ptr_eq(slot, &self.slot));
+    }
+}
+
+impl Shared {
+    /// Nothing in [`State`] can be left half-updated by a panic, so a poisoned lock can safely be
+    /// recovered.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn now(&self) -> u64 {
+        self.epoch
+            .elapsed()
+            .as_nanos()
+            .try_into()
+            .unwrap_or(u64::MAX)
+    }
+
+    fn last_feed(&self, slot: &Slot) -> Instant {
+        self.epoch + Duration::from_nanos(slot.last_feed.load(Relaxed))
+    }
+
+    fn schedule(this: &Arc<Self>, at: Instant) {
+        let shared = Arc::downgrade(this);
+        timer::schedule(at, move || Self::check(shared));
+    }
+
+    /// Runs on the timer thread: flip the switch if any feeder has expired, and otherwise schedule
+    /// the next check for when the least recently fed feeder would expire
+    fn check(shared: Weak<Self>) {
+        let Some(shared) = shared.upgrade() else {
+            return;
+        };
+        let mut state = shared.lock();
+        if !shared.kill.is_alive() {
+            state.scheduled = false;
+            return;
+        }
+
+        let oldest = state
+            .feeders
+            .iter()
+            .map(|slot| (slot, shared.last_feed(slot)))
+            .min_by_key(|(_, last_feed)| *last_feed);
+        let Some((slot, last_feed)) = oldest else {
+            state.scheduled = false;
+            return;
+        };
+
+        let Some(expiry) = last_feed.checked_add(shared.interval) else {
+            state.scheduled = false;
+            return;
+        };
+        if expiry <= Instant::now() {
+            let reason = MissedFeed {
+                feeder: slot.name.clone(),
+                last_feed,
+            };
+            state.scheduled = false;
+            drop(state);
+            let _ = shared.kill.kill_with(reason);
+        } else {
+            drop(state);
+            Self::schedule(&shared, expiry);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use killswitch_std::KillSwitch;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+};
+
+#[test]
+fn callbacks_run_once_on_killing_thread() {
+    let kill = KillSwitch::default();
+    let count = Arc::new(AtomicUsize::new(0));
+    let (tx, rx) = mpsc::channel();
+
+    let handles: Vec<_> = (0..5)
+        .map(|_| {
+            let count = count.clone();
+            let tx = tx.clone();
+            kill.on_kill(move || {
+                count.fetch_add(1, Ordering::Relaxed);
+                tx.send(thread::current().id()).unwrap();
+            })
+        })
+        .collect();
+
+    let k = kill.clone();
+    let killer = thread::spawn(move || {
+        k.kill().unwrap();
+        thread::current().id()
+    })
+    .join()
+    .unwrap();
+
+    assert!(kill.kill().is_err());
+    drop(handles);
+    drop(tx);
+
+    assert_eq!(count.load(Ordering::Relaxed), 5);
+    assert!(rx.iter().all(|id| id == killer));
+}
+
+#[test]
+fn callback_runs_immediately_when_already_killed() {
+    let kill = KillSwitch::default();
+    kill.kill().unwrap();
+
+    let count = Arc::new(AtomicUsize::new(0));
+    let c = count.clone();
+    let _handle = kill.on_kill(move || {
+        c.fetch_add(1, Ordering::Relaxed);
+    });
+
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn dropped_handle_unregisters() {
+    let kill = KillSwitch::default();
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let c = count.clone();
+    drop(kill.on_kill(move || {
+        c.fetch_add(1, Ordering::Relaxed);
+    }));
+    let c = count.clone();
+    kill.on_kill(move || {
+        c.fetch_add(10, Ordering::Relaxed);
+    })
+    .detach();
+
+    kill.kill().unwrap();
+    assert_eq!(count.load(Ordering::Relaxed), 10);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn panicking_callback_is_isolated() {
+    let kill = KillSwitch::default();
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let c1 = count.clone();
+    let _h1 = kill.on_kill(move || {
+        c1.fetch_add(1, Ordering::Relaxed);
+    });
+    let _h2 = kill.on_kill(|| panic!("bad callback"));
+    let c3 = count.clone();
+    let _h3 = kill.on_kill(move || {
+        c3.fetch_add(1, Ordering::Relaxed);
+    });
+
+    kill.kill().unwrap();
+    assert_eq!(count.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn unregistering_callback_can_drop_switch_state() {
+    let parent = KillSwitch::default();
+
+    // Dropping the child removes it from the parent, which must not happen under the parent's lock
+    let child = parent.child();
+    let handle = parent.on_kill(move || drop(child));
+    drop(handle);
+
+    // Dropping the last handle of a dead-man's switch kills it, which takes its lock again
+    let dead_man = KillSwitch::dead_man();
+    let w = dead_man.watcher();
+    let captured = dead_man.clone();
+    let handle = dead_man.on_kill(move || drop(captured));
+    drop(dead_man);
+    drop(handle);
+    assert!(!w.is_alive());
+
+    assert!(parent.is_alive());
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "std")]
+
+use killswitch_std::{KillSwitch, RecvOrKilled};
+use std::{
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn recv_wakes_on_kill() {
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+    let (_tx, rx) = mpsc::channel::<()>();
+
+    let t = thread::spawn(move || {
+        let start = Instant::now();
+        (w.recv(&rx), start.elapsed())
+    });
+
+    thread::sleep(Duration::from_millis(100));
+    kill.kill().unwrap();
+
+    let (result, elapsed) = t.join().unwrap();
+    assert_eq!(result, Err(RecvOrKilled::Killed));
+    assert!(elapsed < Duration::from_secs(5));
+}
+
+#[test]
+fn recv_timeout_reports_each_outcome() {
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+    let (tx, rx) = mpsc::channel();
+
+    tx.send(1).unwrap();
+    assert_eq!(w.recv_timeout(&rx, Duration::from_millis(50)), Ok(1));
+    tx.send(2).unwrap();
+    assert_eq!(w.recv_timeout(&rx, Duration::ZERO), Ok(2));
+    assert_eq!(
+        w.recv_timeout(&rx, Duration::ZERO),
+        Err(RecvOrKilled::Timeout)
+    );
+
+    let start = Instant::now();
+    assert_eq!(
+        w.recv_timeout(&rx, Duration::from_millis(50)),
+        Err(RecvOrKilled::Timeout)
+    );
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    drop(tx);
+    assert_eq!(w.recv(&rx), Err(RecvOrKilled::Disconnected));
+}
+
+#[test]
+fn recv_iter_stops_on_kill_or_disconnect() {
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+
+    let (tx, rx) = mpsc::channel();
+    for i in 0..3 {
+        tx.send(i).unwrap();
+    }
+    drop(tx);
+    assert_eq!(w.recv_iter(&rx).collect::<Vec<_>>(), [0, 1, 2]);
+
+    let (tx, rx) = mpsc::channel();
+    let producer = thread::spawn(move || {
+        for i in 0.. {
+            if tx.send(i).is_err() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+    });
+    let mut received = 0;
+    for i in w.recv_iter(&rx) {
+        assert_eq!(i, received);
+        received += 1;
+        if received == 10 {
+            kill.kill().unwrap();
+        }
+    }
+    assert_eq!(received, 10);
+    drop(rx);
+    producer.join().unwrap();
+}
+
+#[test]
+fn killable_channel_wakes_on_kill() {
+    let kill = KillSwitch::default();
+    let (tx, rx) = kill.watcher().channel::<u32>();
+
+    tx.send(1).unwrap();
+    assert_eq!(rx.recv(), Ok(1));
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(20)),
+        Err(RecvOrKilled::Timeout)
+    );
+
+    let t = thread::spawn(move || {
+        let start = Instant::now();
+        (rx.recv(), start.elapsed())
+    });
+    thread::sleep(Duration::from_millis(100));
+    kill.kill().unwrap();
+
+    let (result, elapsed) = t.join().unwrap();
+    assert_eq!(result, Err(RecvOrKilled::Killed));
+    assert!(elapsed < Duration::from_secs(5));
+    assert!(tx.send(2).is_err());
+}
+
+#[test]
+fn killable_channel_disconnects_when_senders_dropped() {
+    let kill = KillSwitch::default();
+    let (tx, rx) = kill.watcher().channel();
+    let tx2 = tx.clone();
+    tx.send(1).unwrap();
+    tx2.send(2).unwrap();
+    drop(tx);
+    drop(tx2);
+
+    assert_eq!(rx.iter().collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(rx.recv(), Err(RecvOrKilled::Disconnected));
+
+    // Once killed, a disconnected channel reports the kill instead
+    kill.kill().unwrap();
+    assert_eq!(rx.recv(), Err(RecvOrKilled::Killed));
+}
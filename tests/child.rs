@@ -0,0 +1,64 @@
+use killswitch_std::KillSwitch;
+
+#[test]
+#[cfg(feature = "std")]
+fn parent_kills_descendants() {
+    use std::{thread, time::Duration};
+
+    let server = KillSwitch::default();
+    let connection = server.child();
+    let request = connection.child();
+    let w = request.watcher();
+
+    let t = thread::spawn(move || w.wait_timeout(Duration::from_secs(5)));
+
+    server.kill().unwrap();
+
+    assert!(!server.is_alive());
+    assert!(!connection.is_alive());
+    assert!(!request.is_alive());
+    assert!(t.join().unwrap());
+}
+
+#[test]
+fn child_does_not_kill_parent_or_siblings() {
+    let parent = KillSwitch::default();
+    let c1 = parent.child();
+    let c2 = parent.child();
+
+    c1.kill().unwrap();
+
+    assert!(!c1.is_alive());
+    assert!(c2.is_alive());
+    assert!(parent.is_alive());
+
+    // Killing the parent afterwards still reaches the surviving child
+    parent.kill().unwrap();
+    assert!(!c2.is_alive());
+}
+
+#[test]
+fn child_of_killed_parent_is_killed() {
+    let parent = KillSwitch::default();
+    parent.kill().unwrap();
+
+    let child = parent.child();
+    assert!(!child.is_alive());
+    assert!(child.kill().is_err());
+}
+
+#[test]
+fn dropped_children_are_released() {
+    let parent = KillSwitch::default();
+    for _ in 0..100_000 {
+        let child = parent.child();
+        let _w = child.watcher();
+    }
+
+    // Children outliving their watchers still get killed
+    let child = parent.child();
+    let w = child.watcher();
+    drop(child);
+    parent.kill().unwrap();
+    assert!(!w.is_alive());
+}
@@ -0,0 +1,85 @@
+use killswitch_std::{KillSwitch, KillSwitchWatcher};
+use std::time::Duration;
+
+#[test]
+fn any_of_fires_on_first() {
+    let global = KillSwitch::default();
+    let task = KillSwitch::default();
+    let either = KillSwitchWatcher::any_of(&[global.watcher(), task.watcher()]);
+
+    assert!(either.is_alive());
+    task.kill_with("task cancelled").unwrap();
+    assert!(!either.is_alive());
+    assert_eq!(either.reason().unwrap().to_string(), "task cancelled");
+    assert!(global.is_alive());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn all_of_waits_for_every_input() {
+    use std::thread;
+
+    let switches: Vec<_> = (0..3).map(|_| KillSwitch::default()).collect();
+    let watchers: Vec<_> = switches.iter().map(|k| k.watcher()).collect();
+    let all = KillSwitchWatcher::all_of(&watchers);
+
+    let w = all.clone();
+    let t = thread::spawn(move || w.wait_timeout(Duration::from_secs(5)));
+
+    switches[0].kill().unwrap();
+    switches[2].kill().unwrap();
+    assert!(all.is_alive());
+    switches[1].kill_with("last").unwrap();
+
+    assert!(t.join().unwrap());
+    assert_eq!(all.reason().unwrap().to_string(), "last");
+}
+
+#[test]
+fn combinators_with_already_killed_inputs() {
+    let dead = KillSwitch::default();
+    dead.kill().unwrap();
+    let alive = KillSwitch::default();
+
+    assert!(!KillSwitchWatcher::any_of(&[alive.watcher(), dead.watcher()]).is_alive());
+    assert!(KillSwitchWatcher::all_of(&[alive.watcher(), dead.watcher()]).is_alive());
+    assert!(!KillSwitchWatcher::all_of(&[dead.watcher(), dead.watcher()]).is_alive());
+
+    assert!(KillSwitchWatcher::any_of(&[]).is_alive());
+    assert!(!KillSwitchWatcher::all_of(&[]).is_alive());
+}
+
+#[tokio::test]
+async fn any_of_is_awaitable() {
+    let global = KillSwitch::default();
+    let task = KillSwitch::default();
+    let either = KillSwitchWatcher::any_of(&[global.watcher(), task.watcher()]);
+
+    let handle = tokio::spawn(async move { either.killed().await });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    global.kill().unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), handle)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn wait_any_reports_first_killed() {
+    use std::thread;
+
+    let switches: Vec<_> = (0..4).map(|_| KillSwitch::default()).collect();
+    let watchers: Vec<_> = switches.iter().map(|k| k.watcher()).collect();
+
+    let t = thread::spawn(move || KillSwitchWatcher::wait_any(&watchers));
+    thread::sleep(Duration::from_millis(50));
+    switches[2].kill().unwrap();
+    switches[1].kill().unwrap();
+
+    assert_eq!(t.join().unwrap(), 2);
+
+    let watchers: Vec<_> = switches.iter().map(|k| k.watcher()).collect();
+    assert_eq!(KillSwitchWatcher::wait_any(&watchers), 1);
+}
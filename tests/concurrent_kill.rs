@@ -0,0 +1,101 @@
+use killswitch_std::{KillSwitch, KillSwitchErr};
+use std::{
+    sync::{Arc, Barrier},
+    thread,
+};
+
+const THREADS: usize = 8;
+const ROUNDS: usize = 500;
+
+#[test]
+fn exactly_one_winner() {
+    for _ in 0..ROUNDS {
+        let kill = KillSwitch::default();
+        let barrier = Arc::new(Barrier::new(THREADS));
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let kill = kill.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    kill.kill().is_ok()
+                })
+            })
+            .collect();
+
+        let winners = handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .filter(|won| *won)
+            .count();
+        assert_eq!(winners, 1);
+        assert!(!kill.is_alive());
+    }
+}
+
+#[test]
+fn losers_see_winning_reason() {
+    for _ in 0..ROUNDS {
+        let kill = KillSwitch::default();
+        let barrier = Arc::new(Barrier::new(THREADS));
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let kill = kill.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    match kill.kill_with(format!("thread {t}")) {
+                        Ok(_token) => Ok(t),
+                        Err(KillSwitchErr::AlreadyKilled(reason)) => {
+                            Err(reason.expect("winner's reason missing").to_string())
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        let winners: Vec<_> = results.iter().filter_map(|r| r.as_ref().ok()).collect();
+        assert_eq!(winners.len(), 1);
+
+        let expected = format!("thread {}", winners[0]);
+        assert_eq!(kill.reason().unwrap().to_string(), expected);
+        for loser in results.iter().filter_map(|r| r.as_ref().err()) {
+            assert_eq!(loser, &expected);
+        }
+    }
+}
+
+#[test]
+fn parent_and_child_race() {
+    for _ in 0..ROUNDS {
+        let parent = KillSwitch::default();
+        let child = parent.child();
+        let barrier = Arc::new(Barrier::new(2));
+
+        let p = {
+            let parent = parent.clone();
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                parent.kill().is_ok()
+            })
+        };
+        let c = {
+            let child = child.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                child.kill().is_ok()
+            })
+        };
+
+        // The parent always wins its own switch, whereas the child is flipped exactly once by
+        // either its own kill or the parent's
+        assert!(p.join().unwrap());
+        c.join().unwrap();
+        assert!(!child.is_alive());
+        assert!(child.kill().is_err());
+    }
+}
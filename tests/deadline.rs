@@ -0,0 +1,98 @@
+#![cfg(feature = "std")]
+
+use killswitch_std::{DeadlineExpired, KillSwitch};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn with_deadline_kills_on_time() {
+    let deadline = Instant::now() + Duration::from_millis(100);
+    let kill = KillSwitch::with_deadline(deadline);
+    let w = kill.watcher();
+
+    assert!(w.is_alive());
+    assert_eq!(w.deadline(), Some(deadline));
+    assert!(w.remaining().unwrap() <= Duration::from_millis(100));
+
+    assert!(w.wait_timeout(Duration::from_secs(5)));
+    assert!(Instant::now() >= deadline);
+    assert_eq!(w.remaining(), Some(Duration::ZERO));
+
+    let reason = w.reason().unwrap();
+    assert_eq!(
+        reason.downcast_ref::<DeadlineExpired>(),
+        Some(&DeadlineExpired { deadline })
+    );
+}
+
+#[test]
+fn many_deadlines_share_timer() {
+    let switches: Vec<_> = (0..100)
+        .map(|i| KillSwitch::with_deadline(Instant::now() + Duration::from_millis(10 * (i % 10))))
+        .collect();
+
+    for kill in &switches {
+        assert!(kill.wait_timeout(Duration::from_secs(5)));
+    }
+}
+
+#[test]
+fn earliest_deadline_wins() {
+    let kill = KillSwitch::default();
+    assert_eq!(kill.deadline(), None);
+    assert_eq!(kill.remaining(), None);
+
+    kill.kill_after(Duration::from_secs(60));
+    kill.kill_after(Duration::from_millis(50));
+    let deadline = kill.deadline().unwrap();
+    kill.kill_after(Duration::from_secs(60));
+    assert_eq!(kill.deadline(), Some(deadline));
+
+    assert!(kill.wait_timeout(Duration::from_secs(5)));
+}
+
+#[test]
+fn manual_kill_is_distinguished() {
+    let kill = KillSwitch::default();
+    kill.kill_after(Duration::from_millis(50));
+    kill.kill_with("operator request").unwrap();
+
+    thread::sleep(Duration::from_millis(100));
+    let reason = kill.reason().unwrap();
+    assert!(reason.downcast_ref::<DeadlineExpired>().is_none());
+    assert_eq!(reason.to_string(), "operator request");
+}
+
+#[test]
+fn past_deadline_kills_immediately() {
+    let kill = KillSwitch::with_deadline(Instant::now());
+    assert!(!kill.is_alive());
+    assert!(kill.reason().unwrap().is::<DeadlineExpired>());
+}
+
+#[test]
+fn many_short_lived_deadlines_are_pruned() {
+    let kept = KillSwitch::default();
+    kept.kill_after(Duration::from_secs(3600));
+
+    // Enough cancelled entries to trigger pruning several times over
+    for i in 0..10_000 {
+        let kill = KillSwitch::default();
+        kill.kill_after(Duration::from_secs(3600));
+        if i % 2 == 0 {
+            kill.kill().unwrap();
+        }
+    }
+
+    // Replacing a deadline cancels the old entry, but not the new one
+    let soon = KillSwitch::default();
+    soon.kill_after(Duration::from_secs(3600));
+    soon.kill_after(Duration::from_millis(50));
+    assert!(soon.wait_timeout(Duration::from_secs(5)));
+    assert!(soon.reason().unwrap().is::<DeadlineExpired>());
+
+    assert!(kept.is_alive());
+    assert!(kept.remaining().unwrap() > Duration::from_secs(3000));
+}
@@ -0,0 +1,67 @@
+use killswitch_std::KillSwitch;
+
+#[test]
+fn enter_fails_once_killed() {
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+
+    let guard = w.enter();
+    assert!(guard.is_some());
+    kill.kill().unwrap();
+    assert!(w.enter().is_none());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn kill_and_drain_waits_for_guards() {
+    use killswitch_std::DrainResult;
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    let kill = KillSwitch::default();
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let guard = kill.watcher().enter().unwrap();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(100));
+                drop(guard);
+            })
+        })
+        .collect();
+
+    let start = Instant::now();
+    assert_eq!(
+        kill.kill_and_drain(Duration::from_secs(5)),
+        DrainResult::Drained
+    );
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    assert!(!kill.is_alive());
+    for w in workers {
+        w.join().unwrap();
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn kill_and_drain_reports_stragglers() {
+    use killswitch_std::DrainResult;
+    use std::time::Duration;
+
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+    let _a = w.enter().unwrap();
+    let b = w.enter().unwrap();
+    drop(w.enter().unwrap());
+
+    assert_eq!(
+        kill.kill_and_drain(Duration::from_millis(50)),
+        DrainResult::TimedOut { in_flight: 2 }
+    );
+    drop(b);
+    assert_eq!(
+        kill.kill_and_drain(Duration::from_millis(50)),
+        DrainResult::TimedOut { in_flight: 1 }
+    );
+}
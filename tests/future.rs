@@ -0,0 +1,126 @@
+use killswitch_std::KillSwitch;
+use std::{
+    future::Future,
+    pin::pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+    time::{Duration, Instant},
+};
+
+/// Minimal executor which parks the current thread between polls, panicking if the future has not
+/// resolved by the deadline (i.e. if a wake-up was lost)
+fn block_on_deadline<F: Future>(fut: F, timeout: Duration) -> F::Output {
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let deadline = Instant::now() + timeout;
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = pin!(fut);
+    loop {
+        if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+            return out;
+        }
+        let now = Instant::now();
+        assert!(now < deadline, "future was never woken");
+        thread::park_timeout(deadline - now);
+    }
+}
+
+#[tokio::test]
+async fn killed_resolves_in_tokio() {
+    let kill = KillSwitch::default();
+
+    let mut handles = Vec::new();
+    for _ in 0..10 {
+        let w = kill.watcher();
+        handles.push(tokio::spawn(async move {
+            w.killed().await;
+            assert!(!w.is_alive());
+        }));
+    }
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    kill.kill().unwrap();
+
+    for h in handles {
+        tokio::time::timeout(Duration::from_secs(5), h)
+            .await
+            .expect("task was never woken")
+            .unwrap();
+    }
+}
+
+#[test]
+fn killed_is_ready_after_kill() {
+    let kill = KillSwitch::default();
+    kill.kill().unwrap();
+
+    let waker = Waker::noop();
+    let mut cx = Context::from_waker(waker);
+    assert!(pin!(kill.killed()).poll(&mut cx).is_ready());
+    assert!(pin!(kill.watcher().killed()).poll(&mut cx).is_ready());
+}
+
+#[test]
+fn killed_is_pending_while_alive() {
+    let kill = KillSwitch::default();
+
+    let waker = Waker::noop();
+    let mut cx = Context::from_waker(waker);
+    let mut fut = pin!(kill.watcher().killed());
+    assert!(fut.as_mut().poll(&mut cx).is_pending());
+    assert!(fut.as_mut().poll(&mut cx).is_pending());
+
+    kill.kill().unwrap();
+    assert!(fut.as_mut().poll(&mut cx).is_ready());
+}
+
+#[test]
+fn no_lost_wakeup_when_kill_races_registration() {
+    for _ in 0..1000 {
+        let kill = KillSwitch::default();
+        let w = kill.watcher();
+
+        let killer = thread::spawn(move || {
+            kill.kill().unwrap();
+        });
+        block_on_deadline(w.killed(), Duration::from_secs(5));
+        killer.join().unwrap();
+    }
+}
+
+#[test]
+fn dropped_future_is_deregistered() {
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let kill = KillSwitch::default();
+
+    let count = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker = Waker::from(count.clone());
+    let mut cx = Context::from_waker(&waker);
+    for _ in 0..100 {
+        let mut fut = pin!(kill.killed());
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+    }
+    let mut kept = pin!(kill.killed());
+    assert!(kept.as_mut().poll(&mut cx).is_pending());
+
+    kill.kill().unwrap();
+    assert_eq!(count.0.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,70 @@
+use killswitch_std::{IteratorExt, KillSwitch};
+
+#[test]
+fn stops_once_killed() {
+    let kill = KillSwitch::default();
+    let mut items = (0..100).until_killed(kill.watcher());
+
+    let mut seen = 0;
+    for i in &mut items {
+        seen += 1;
+        if i == 9 {
+            kill.kill().unwrap();
+        }
+    }
+    assert_eq!(seen, 10);
+    assert!(items.was_killed());
+    assert_eq!(items.next(), None);
+}
+
+#[test]
+fn runs_to_completion_while_alive() {
+    let kill = KillSwitch::default();
+    let mut items = (0..100).until_killed(kill.watcher()).check_every(7);
+    assert_eq!((&mut items).sum::<i32>(), 4950);
+    assert!(!items.was_killed());
+}
+
+#[test]
+fn stride_limits_items_after_kill() {
+    let kill = KillSwitch::default();
+    let mut items = (0..100).until_killed(kill.watcher()).check_every(8);
+
+    let mut seen = Vec::new();
+    for i in &mut items {
+        seen.push(i);
+        if i == 2 {
+            kill.kill().unwrap();
+        }
+    }
+    // The kill is only noticed at the next check, before the ninth item
+    assert_eq!(seen, (0..8).collect::<Vec<_>>());
+    assert!(items.was_killed());
+    assert_eq!(items.into_inner().next(), Some(8));
+}
+
+#[test]
+fn already_killed_yields_nothing() {
+    let kill = KillSwitch::default();
+    kill.kill().unwrap();
+    let mut items = [1, 2, 3].into_iter().until_killed(kill.watcher());
+    assert_eq!(items.next(), None);
+    assert!(items.was_killed());
+}
+
+#[test]
+fn kill_on_last_item_is_not_cut_short() {
+    let kill = KillSwitch::default();
+    let mut items = (0..3).until_killed(kill.watcher());
+
+    let mut seen = 0;
+    for i in &mut items {
+        seen += 1;
+        if i == 2 {
+            kill.kill().unwrap();
+        }
+    }
+    assert_eq!(seen, 3);
+    assert!(!items.was_killed());
+    assert_eq!(items.into_inner().next(), None);
+}
@@ -0,0 +1,72 @@
+use killswitch_std::{KillSwitch, Level};
+
+#[test]
+fn levels_only_move_forward() {
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+    assert_eq!(w.level(), Level::ALIVE);
+
+    assert!(kill.advance(Level::STOPPING));
+    assert!(!kill.advance(Level::DRAINING));
+    assert!(!kill.advance(Level::STOPPING));
+    assert_eq!(w.level(), Level::STOPPING);
+    assert!(w.is_alive());
+
+    // Custom levels slot in between the named ones
+    assert!(kill.advance(Level(10)));
+    assert_eq!(w.level().to_string(), "level 10");
+
+    assert!(kill.advance(Level::KILLED));
+    assert!(!w.is_alive());
+    assert!(kill.kill().is_err());
+    assert!(!kill.advance(Level::KILLED));
+}
+
+#[test]
+fn killing_skips_straight_to_killed() {
+    let kill = KillSwitch::default();
+    kill.kill().unwrap();
+    assert_eq!(kill.level(), Level::KILLED);
+    assert!(!kill.advance(Level::DRAINING));
+}
+
+#[test]
+fn children_follow_parent_level() {
+    let parent = KillSwitch::default();
+    let early = parent.child();
+    parent.advance(Level::DRAINING);
+    let late = parent.child();
+
+    assert_eq!(early.level(), Level::DRAINING);
+    assert_eq!(late.level(), Level::DRAINING);
+
+    // Children may move ahead of their parent
+    late.advance(Level::STOPPING);
+    assert_eq!(parent.level(), Level::DRAINING);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn wait_for_level_wakes_on_advance() {
+    use std::{thread, time::Duration};
+
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+    let t = thread::spawn(move || {
+        w.wait_for_level(Level::DRAINING);
+        let level = w.level();
+        (
+            level,
+            w.wait_for_level_timeout(Level::STOPPING, Duration::from_millis(50)),
+        )
+    });
+
+    thread::sleep(Duration::from_millis(50));
+    kill.advance(Level::DRAINING);
+    let (level, stopped) = t.join().unwrap();
+    assert_eq!(level, Level::DRAINING);
+    assert!(!stopped);
+
+    kill.kill().unwrap();
+    assert!(kill.wait_for_level_timeout(Level::STOPPING, Duration::from_secs(5)));
+}
@@ -0,0 +1,174 @@
+//! Model-checked tests of the memory ordering guarantees of a kill switch. Run with
+//! `RUSTFLAGS="--cfg loom" cargo test --test loom --release`.
+#![cfg(loom)]
+
+use killswitch_std::KillSwitch;
+use loom::{cell::UnsafeCell, sync::Arc, thread};
+
+/// Data written by the killing thread without any synchronisation of its own, so that loom
+/// reports a data race unless the kill switch itself orders the accesses
+struct Published(UnsafeCell<usize>);
+
+unsafe impl Sync for Published {}
+
+impl Published {
+    fn new() -> Arc<Self> {
+        Arc::new(Self(UnsafeCell::new(0)))
+    }
+
+    fn write(&self) {
+        self.0.with_mut(|p| unsafe { *p = 42 });
+    }
+
+    fn read(&self) -> usize {
+        self.0.with(|p| unsafe { *p })
+    }
+}
+
+#[test]
+fn is_alive_acquires_kill() {
+    loom::model(|| {
+        let kill = KillSwitch::default();
+        let data = Published::new();
+
+        let w = kill.watcher();
+        let d = data.clone();
+        let t = thread::spawn(move || {
+            if !w.is_alive() {
+                assert_eq!(d.read(), 42);
+            }
+        });
+
+        data.write();
+        kill.kill().unwrap();
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn switch_is_alive_acquires_kill() {
+    loom::model(|| {
+        let kill = KillSwitch::default();
+        let data = Published::new();
+
+        let k = kill.clone();
+        let d = data.clone();
+        let t = thread::spawn(move || {
+            d.write();
+            k.kill().unwrap();
+        });
+
+        if !kill.is_alive() {
+            assert_eq!(data.read(), 42);
+        }
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn wait_acquires_kill() {
+    loom::model(|| {
+        let kill = KillSwitch::default();
+        let data = Published::new();
+
+        let w = kill.watcher();
+        let d = data.clone();
+        let t = thread::spawn(move || {
+            w.wait();
+            assert_eq!(d.read(), 42);
+        });
+
+        data.write();
+        kill.kill().unwrap();
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn killed_future_acquires_kill() {
+    loom::model(|| {
+        let kill = KillSwitch::default();
+        let data = Published::new();
+
+        let w = kill.watcher();
+        let d = data.clone();
+        let t = thread::spawn(move || {
+            loom::future::block_on(w.killed());
+            assert_eq!(d.read(), 42);
+        });
+
+        data.write();
+        kill.kill().unwrap();
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn child_acquires_parent_kill() {
+    loom::model(|| {
+        let parent = KillSwitch::default();
+        let child = parent.child();
+        let data = Published::new();
+
+        let w = child.watcher();
+        let d = data.clone();
+        let t = thread::spawn(move || {
+            if !w.is_alive() {
+                assert_eq!(d.read(), 42);
+            }
+        });
+
+        data.write();
+        parent.kill().unwrap();
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn reason_visible_after_kill() {
+    loom::model(|| {
+        let kill = KillSwitch::default();
+
+        let w = kill.watcher();
+        let t = thread::spawn(move || {
+            if !w.is_alive() {
+                assert_eq!(w.reason().unwrap().to_string(), "done");
+            }
+        });
+
+        kill.kill_with("done").unwrap();
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn racing_kills_have_one_winner() {
+    loom::model(|| {
+        let kill = KillSwitch::default();
+
+        let k = kill.clone();
+        let t = thread::spawn(move || k.kill().is_ok());
+
+        let won = kill.kill().is_ok();
+        assert!(won ^ t.join().unwrap());
+    });
+}
+
+#[test]
+fn kill_and_drain_sees_every_guard() {
+    use killswitch_std::DrainResult;
+    use std::time::Duration;
+
+    loom::model(|| {
+        let kill = KillSwitch::default();
+        let w = kill.watcher();
+        let held = w.enter().unwrap();
+
+        let t1 = thread::spawn(move || drop(held));
+        let t2 = thread::spawn(move || drop(w.enter()));
+
+        assert_eq!(kill.kill_and_drain(Duration::MAX), DrainResult::Drained);
+        t1.join().unwrap();
+        t2.join().unwrap();
+    });
+}
@@ -0,0 +1,56 @@
+use killswitch_std::{KillSwitch, Orphaned};
+
+#[test]
+fn dropping_last_owner_kills() {
+    let kill = KillSwitch::dead_man();
+    let w = kill.watcher();
+    let clone = kill.clone();
+
+    drop(kill);
+    assert!(w.is_alive());
+
+    drop(clone);
+    assert!(!w.is_alive());
+    assert!(w.reason().unwrap().is::<Orphaned>());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn panicking_controller_releases_workers() {
+    use std::{thread, time::Duration};
+
+    let kill = KillSwitch::dead_man();
+    let w = kill.watcher();
+
+    let worker = thread::spawn(move || w.wait_timeout(Duration::from_secs(5)));
+    let controller = thread::spawn(move || {
+        let _kill = kill;
+        panic!("controller failed");
+    });
+
+    assert!(controller.join().is_err());
+    assert!(worker.join().unwrap());
+}
+
+#[test]
+fn orphan_kill_is_opt_in() {
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+    drop(kill);
+    assert!(w.is_alive());
+
+    let kill = KillSwitch::default();
+    kill.kill_when_orphaned();
+    let w = kill.watcher();
+    drop(kill);
+    assert!(!w.is_alive());
+}
+
+#[test]
+fn explicit_kill_keeps_reason() {
+    let kill = KillSwitch::dead_man();
+    let w = kill.watcher();
+    kill.kill_with("done").unwrap();
+    drop(kill);
+    assert_eq!(w.reason().unwrap().to_string(), "done");
+}
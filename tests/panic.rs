@@ -0,0 +1,82 @@
+#![cfg(feature = "std")]
+
+use killswitch_std::{KillSwitch, Panicked};
+use std::{thread, time::Duration};
+
+#[test]
+fn guard_kills_on_scope_exit() {
+    let kill = KillSwitch::default();
+    {
+        let _guard = kill.guard();
+        assert!(kill.is_alive());
+    }
+    assert!(!kill.is_alive());
+    assert!(kill.reason().is_none());
+}
+
+#[test]
+fn guard_kills_on_panic() {
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+
+    let worker = thread::Builder::new()
+        .name("worker".into())
+        .spawn(move || {
+            let _guard = kill.guard();
+            panic!("worker crashed");
+        })
+        .unwrap();
+
+    assert!(w.wait_timeout(Duration::from_secs(5)));
+    assert!(worker.join().is_err());
+
+    let reason = w.reason().unwrap();
+    let panicked = reason.downcast_ref::<Panicked>().unwrap();
+    assert_eq!(panicked.thread.as_deref(), Some("worker"));
+}
+
+#[test]
+fn disarmed_guard_does_not_kill() {
+    let kill = KillSwitch::default();
+    kill.guard().disarm();
+    assert!(kill.is_alive());
+}
+
+#[test]
+fn catch_unwind_records_panic_message() {
+    let kill = KillSwitch::default();
+    let watchers: Vec<_> = (0..3).map(|_| kill.watcher()).collect();
+
+    let k = kill.clone();
+    let result = thread::Builder::new()
+        .name("crashing".into())
+        .spawn(move || k.catch_unwind(|| panic!("bad input {}", 42)))
+        .unwrap()
+        .join()
+        .unwrap();
+    assert!(result.is_err());
+
+    for w in watchers {
+        assert!(!w.is_alive());
+    }
+    let reason = kill.reason().unwrap();
+    assert_eq!(
+        reason.downcast_ref::<Panicked>(),
+        Some(&Panicked {
+            message: Some("bad input 42".into()),
+            thread: Some("crashing".into()),
+            location: None,
+        })
+    );
+    assert_eq!(
+        reason.to_string(),
+        "thread 'crashing' panicked: bad input 42"
+    );
+}
+
+#[test]
+fn catch_unwind_passes_through_success() {
+    let kill = KillSwitch::default();
+    assert_eq!(kill.catch_unwind(|| 7).unwrap(), 7);
+    assert!(kill.is_alive());
+}
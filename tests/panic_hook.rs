@@ -0,0 +1,51 @@
+#![cfg(feature = "std")]
+
+use killswitch_std::{KillSwitch, Panicked};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+// The panic hook is process-wide, so everything is checked in a single test
+#[test]
+fn first_panic_kills_switch() {
+    let previous_calls = Arc::new(AtomicUsize::new(0));
+    let calls = previous_calls.clone();
+    std::panic::set_hook(Box::new(move |_| {
+        calls.fetch_add(1, Ordering::Relaxed);
+    }));
+
+    let shutdown = KillSwitch::default();
+    shutdown.kill_on_panic();
+    let w = shutdown.watcher();
+    assert!(w.is_alive());
+
+    let line = line!() + 4;
+    let first = thread::Builder::new()
+        .name("first".into())
+        .spawn(|| {
+            panic!("first failure");
+        })
+        .unwrap();
+    assert!(first.join().is_err());
+
+    assert!(!w.is_alive());
+    let reason = w.reason().unwrap();
+    let panicked = reason.downcast_ref::<Panicked>().unwrap();
+    assert_eq!(panicked.message.as_deref(), Some("first failure"));
+    assert_eq!(panicked.thread.as_deref(), Some("first"));
+    let location = panicked.location.as_deref().unwrap();
+    assert!(location.starts_with(&format!("{}:{line}:", file!())));
+
+    // Later panics still reach the previous hook, but do not replace the original reason
+    let second = thread::spawn(|| panic!("second failure"));
+    assert!(second.join().is_err());
+    assert_eq!(
+        w.reason().unwrap().downcast_ref::<Panicked>(),
+        Some(panicked)
+    );
+    assert_eq!(previous_calls.load(Ordering::Relaxed), 2);
+}
@@ -0,0 +1,23 @@
+#![cfg(feature = "std")]
+
+use killswitch_std::KillSwitch;
+use std::{sync::mpsc, thread, time::Duration};
+
+// The panic hook is process-wide, so this lives in its own test binary
+#[test]
+fn panicking_callback_does_not_abort_from_hook() {
+    std::panic::set_hook(Box::new(|_| {}));
+
+    let shutdown = KillSwitch::default();
+    shutdown.kill_on_panic();
+
+    let (tx, rx) = mpsc::channel();
+    let _bad = shutdown.on_kill(|| panic!("bad callback"));
+    let _good = shutdown.on_kill(move || tx.send(()).unwrap());
+
+    let worker = thread::spawn(|| panic!("worker failure"));
+    assert!(worker.join().is_err());
+
+    assert!(!shutdown.is_alive());
+    rx.recv_timeout(Duration::from_secs(5)).unwrap();
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "std")]
+
+use killswitch_std::KillSwitch;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn parked_threads_wake_on_kill() {
+    let kill = KillSwitch::default();
+
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let w = kill.watcher();
+            thread::spawn(move || {
+                let _registration = w.register_current_thread();
+                let start = Instant::now();
+                while w.is_alive() {
+                    thread::park_timeout(Duration::from_secs(10));
+                }
+                start.elapsed()
+            })
+        })
+        .collect();
+
+    thread::sleep(Duration::from_millis(100));
+    kill.kill().unwrap();
+
+    for w in workers {
+        assert!(w.join().unwrap() < Duration::from_secs(5));
+    }
+}
+
+#[test]
+fn registering_after_kill_unparks_immediately() {
+    let kill = KillSwitch::default();
+    kill.kill().unwrap();
+
+    let start = Instant::now();
+    let _registration = kill.watcher().register_current_thread();
+    thread::park_timeout(Duration::from_secs(10));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
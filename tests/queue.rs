@@ -0,0 +1,115 @@
+#![cfg(feature = "std")]
+
+use killswitch_std::{DrainMode, KillSwitch, KillableQueue};
+use std::{sync::mpsc, thread, time::Duration};
+
+#[test]
+fn drain_mode_hands_out_remaining_items() {
+    let kill = KillSwitch::default();
+    let queue = KillableQueue::new(kill.watcher(), 4, DrainMode::Drain);
+    for i in 0..3 {
+        queue.push(i).unwrap();
+    }
+
+    kill.kill().unwrap();
+    assert_eq!(queue.push(3), Err(3));
+    assert_eq!(queue.len(), 3);
+    assert_eq!(queue.pop(), Some(0));
+    assert_eq!(queue.pop(), Some(1));
+    assert_eq!(queue.pop(), Some(2));
+    assert_eq!(queue.pop(), None);
+}
+
+#[test]
+fn discard_mode_drops_remaining_items() {
+    let kill = KillSwitch::default();
+    let queue = KillableQueue::new(kill.watcher(), 4, DrainMode::Discard);
+    queue.push(1).unwrap();
+    queue.push(2).unwrap();
+
+    kill.kill().unwrap();
+    assert!(queue.is_empty());
+    assert_eq!(queue.pop(), None);
+}
+
+#[test]
+fn discard_mode_applies_before_kill_callback_runs() {
+    let kill = KillSwitch::default();
+    let (tx, rx) = mpsc::channel();
+    let _slow = kill.on_kill(move || {
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    });
+    let queue = KillableQueue::new(kill.watcher(), 4, DrainMode::Discard);
+    queue.push(1).unwrap();
+
+    let killer = {
+        let kill = kill.clone();
+        thread::spawn(move || kill.kill().unwrap())
+    };
+    while kill.is_alive() {
+        thread::yield_now();
+    }
+
+    // The queue's own callback is still stuck behind the slow one
+    assert_eq!(queue.pop(), None);
+    assert!(queue.is_empty());
+    tx.send(()).unwrap();
+    killer.join().unwrap();
+}
+
+#[test]
+fn switching_to_discard_after_kill_drops_items() {
+    let kill = KillSwitch::default();
+    let queue = KillableQueue::new(kill.watcher(), 4, DrainMode::Drain);
+    queue.push(1).unwrap();
+    kill.kill().unwrap();
+
+    queue.set_drain_mode(DrainMode::Discard);
+    assert_eq!(queue.pop(), None);
+}
+
+#[test]
+fn blocked_threads_wake_on_kill() {
+    let kill = KillSwitch::default();
+    let full = KillableQueue::new(kill.watcher(), 1, DrainMode::Drain);
+    let empty = KillableQueue::<u32>::new(kill.watcher(), 1, DrainMode::Drain);
+    full.push(0).unwrap();
+
+    let producer = {
+        let full = full.clone();
+        thread::spawn(move || full.push(1))
+    };
+    let consumer = {
+        let empty = empty.clone();
+        thread::spawn(move || empty.pop())
+    };
+
+    thread::sleep(Duration::from_millis(100));
+    kill.kill().unwrap();
+
+    assert_eq!(producer.join().unwrap(), Err(1));
+    assert_eq!(consumer.join().unwrap(), None);
+    assert_eq!(full.pop(), Some(0));
+}
+
+#[test]
+fn bounded_queue_applies_backpressure() {
+    let kill = KillSwitch::default();
+    let queue = KillableQueue::new(kill.watcher(), 2, DrainMode::Drain);
+    assert_eq!(queue.capacity(), 2);
+
+    let producer = {
+        let queue = queue.clone();
+        thread::spawn(move || {
+            for i in 0..100 {
+                queue.push(i).unwrap();
+            }
+        })
+    };
+
+    for i in 0..100 {
+        assert_eq!(queue.pop(), Some(i));
+        assert!(queue.len() <= 2);
+    }
+    producer.join().unwrap();
+}
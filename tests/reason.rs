@@ -0,0 +1,59 @@
+use killswitch_std::{KillSwitch, KillSwitchErr};
+use std::{error::Error, io};
+
+#[test]
+fn reason_visible_to_watchers() {
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+
+    assert!(w.reason().is_none());
+
+    kill.kill_with(io::Error::other("fatal error")).unwrap();
+
+    let reason = w.reason().unwrap();
+    assert_eq!(reason.to_string(), "fatal error");
+    assert!(reason.downcast_ref::<io::Error>().is_some());
+    assert_eq!(kill.reason().unwrap().to_string(), "fatal error");
+}
+
+#[test]
+fn first_reason_wins() {
+    let kill = KillSwitch::default();
+
+    kill.kill_with("operator request").unwrap();
+
+    let err = kill.kill_with("deadline").unwrap_err();
+    let KillSwitchErr::AlreadyKilled(Some(reason)) = &err else {
+        panic!("expected original reason, got {err:?}");
+    };
+    assert_eq!(reason.to_string(), "operator request");
+    assert_eq!(err.source().unwrap().to_string(), "operator request");
+    assert_eq!(kill.reason().unwrap().to_string(), "operator request");
+}
+
+#[test]
+fn plain_kill_has_no_reason() {
+    let kill = KillSwitch::default();
+    kill.kill().unwrap();
+
+    assert!(kill.reason().is_none());
+    assert!(matches!(
+        kill.kill_with("too late"),
+        Err(KillSwitchErr::AlreadyKilled(None))
+    ));
+    assert!(kill.reason().is_none());
+}
+
+#[test]
+fn children_inherit_reason() {
+    let parent = KillSwitch::default();
+    let child = parent.child();
+
+    parent.kill_with("shutting down").unwrap();
+
+    assert_eq!(child.reason().unwrap().to_string(), "shutting down");
+    assert_eq!(
+        parent.child().reason().unwrap().to_string(),
+        "shutting down"
+    );
+}
@@ -0,0 +1,52 @@
+use killswitch_std::{ResettableKillSwitch, Superseded};
+
+#[test]
+fn reset_starts_new_generation() {
+    let kill = ResettableKillSwitch::new();
+    let first = kill.watcher();
+    assert_eq!(first.generation(), 0);
+
+    kill.kill_with("reloading").unwrap();
+    assert!(!kill.is_alive());
+    assert_eq!(kill.reset(), 1);
+
+    let second = kill.watcher();
+    assert!(kill.is_alive());
+    assert!(second.is_alive());
+    assert_eq!(second.generation(), 1);
+    assert_eq!(kill.to_string(), "alive (generation 1)");
+
+    // The old generation stays dead, with its original reason
+    assert!(!first.is_alive());
+    assert_eq!(first.reason().unwrap().to_string(), "reloading");
+    assert!(kill.reason().is_none());
+}
+
+#[test]
+fn reset_kills_live_generation() {
+    let kill = ResettableKillSwitch::new();
+    let switch = kill.current();
+    let child = switch.child();
+
+    kill.reset();
+    kill.reset();
+    assert_eq!(kill.generation(), 2);
+
+    assert!(!switch.is_alive());
+    assert_eq!(child.watcher().generation(), 0);
+    assert_eq!(
+        child.reason().unwrap().downcast_ref::<Superseded>(),
+        Some(&Superseded { generation: 1 })
+    );
+    assert!(switch.kill().is_err());
+}
+
+#[test]
+fn children_inherit_generation() {
+    let kill = ResettableKillSwitch::new();
+    kill.reset();
+    let child = kill.current().child();
+    assert_eq!(child.watcher().generation(), 1);
+    assert!(kill.current().kill().is_ok());
+    assert!(!child.is_alive());
+}
@@ -0,0 +1,166 @@
+#![cfg(all(
+    feature = "signals",
+    target_os = "linux",
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+))]
+
+use killswitch_std::{Escalation, KillSwitch, Signalled, SIGHUP, SIGINT, SIGTERM};
+use std::{
+    ffi::c_int,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+extern "C" {
+    fn raise(signal: c_int) -> c_int;
+    fn signal(signal: c_int, handler: usize) -> usize;
+}
+
+const SIG_DFL: usize = 0;
+const SIG_IGN: usize = 1;
+
+#[test]
+fn signal_kills_registered_switches() {
+    let server = KillSwitch::default();
+    let worker = KillSwitch::default();
+    let reload = KillSwitch::default();
+    server.kill_on_signals(&[SIGTERM]).unwrap();
+    worker.kill_on_signals(&[SIGHUP, SIGTERM]).unwrap();
+    reload.kill_on_signals(&[SIGHUP]).unwrap();
+
+    // SAFETY: a handler is installed for SIGTERM, so this does not terminate the process
+    assert_eq!(unsafe { raise(SIGTERM) }, 0);
+
+    assert!(server.wait_timeout(Duration::from_secs(5)));
+    assert!(worker.wait_timeout(Duration::from_secs(5)));
+    assert!(reload.is_alive());
+
+    let reason = server.reason().unwrap();
+    assert_eq!(
+        reason.downcast_ref::<Signalled>(),
+        Some(&Signalled { signal: SIGTERM })
+    );
+    assert_eq!(reason.to_string(), "received SIGTERM");
+}
+
+#[test]
+fn uncatchable_signal_is_rejected() {
+    let kill = KillSwitch::default();
+    assert!(kill.kill_on_signals(&[9]).is_err());
+}
+
+#[test]
+fn failed_registration_restores_earlier_handlers() {
+    const SIGUSR2: i32 = 12;
+
+    // SAFETY: ignoring a signal is always sound
+    unsafe { signal(SIGUSR2, SIG_IGN) };
+    let kill = KillSwitch::default();
+    assert!(kill.kill_on_signals(&[SIGUSR2, 9]).is_err());
+
+    // SAFETY: as above
+    assert_eq!(unsafe { signal(SIGUSR2, SIG_IGN) }, SIG_IGN);
+}
+
+#[test]
+fn signal_without_live_switches_gets_previous_handler() {
+    const SIGCHLD: i32 = 17;
+    const SIGURG: i32 = 23;
+    const SIGWINCH: i32 = 28;
+
+    // All three signals are ignored by default, so raising them cannot stop the tests. SIGWINCH
+    // is explicitly ignored beforehand, as SIGHUP would be under `nohup`.
+    // SAFETY: ignoring a signal is always sound
+    unsafe { signal(SIGWINCH, SIG_IGN) };
+    KillSwitch::default()
+        .kill_on_signals(&[SIGCHLD, SIGWINCH])
+        .unwrap();
+    let marker = KillSwitch::default();
+    marker.kill_on_signals(&[SIGURG]).unwrap();
+
+    // SAFETY: handlers are installed for all three signals
+    unsafe {
+        assert_eq!(raise(SIGCHLD), 0);
+        assert_eq!(raise(SIGWINCH), 0);
+        assert_eq!(raise(SIGURG), 0);
+    }
+    // Signals are handled in order, so the others have been dealt with once the marker is killed
+    assert!(marker.wait_timeout(Duration::from_secs(5)));
+
+    // SAFETY: as above
+    unsafe {
+        assert_eq!(signal(SIGCHLD, SIG_IGN), SIG_DFL);
+        assert_eq!(signal(SIGWINCH, SIG_IGN), SIG_IGN);
+    }
+}
+
+#[test]
+fn second_signal_escalates() {
+    let (tx, rx) = mpsc::channel();
+    let kill = KillSwitch::default();
+    kill.kill_on_signals_with(
+        &[SIGINT],
+        Escalation::new().hook(move || tx.send(()).unwrap()),
+    )
+    .unwrap();
+
+    // SAFETY: a handler is installed for SIGINT
+    assert_eq!(unsafe { raise(SIGINT) }, 0);
+    assert!(kill.wait_timeout(Duration::from_secs(5)));
+    assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+    // SAFETY: as above
+    assert_eq!(unsafe { raise(SIGINT) }, 0);
+    rx.recv_timeout(Duration::from_secs(5)).unwrap();
+}
+
+#[test]
+fn grace_period_escalates() {
+    const SIGUSR1: i32 = 10;
+
+    let (tx, rx) = mpsc::channel();
+    let kill = KillSwitch::default();
+    kill.kill_on_signals_with(
+        &[SIGUSR1],
+        Escalation::new()
+            .grace(Duration::from_millis(200))
+            .hook(move || tx.send(Instant::now()).unwrap()),
+    )
+    .unwrap();
+
+    let start = Instant::now();
+    // SAFETY: a handler is installed for SIGUSR1
+    assert_eq!(unsafe { raise(SIGUSR1) }, 0);
+    assert!(kill.wait_timeout(Duration::from_secs(5)));
+
+    let escalated = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(escalated - start >= Duration::from_millis(200));
+}
+
+#[test]
+fn endless_grace_period_still_escalates_on_second_signal() {
+    const SIGPWR: i32 = 30;
+
+    let (tx, rx) = mpsc::channel();
+    let kill = KillSwitch::default();
+    kill.kill_on_signals_with(
+        &[SIGPWR],
+        Escalation::new()
+            .grace(Duration::MAX)
+            .hook(move || tx.send(()).unwrap()),
+    )
+    .unwrap();
+
+    // SAFETY: a handler is installed for SIGPWR
+    assert_eq!(unsafe { raise(SIGPWR) }, 0);
+    assert!(kill.wait_timeout(Duration::from_secs(5)));
+    // SAFETY: as above
+    assert_eq!(unsafe { raise(SIGPWR) }, 0);
+    rx.recv_timeout(Duration::from_secs(5)).unwrap();
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "std")]
+
+use killswitch_std::KillSwitch;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn sleep_wakes_early_on_kill() {
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+
+    let t = thread::spawn(move || {
+        let start = Instant::now();
+        let result = w.sleep(Duration::from_secs(10));
+        (result, start.elapsed())
+    });
+
+    thread::sleep(Duration::from_millis(100));
+    kill.kill_with("stop").unwrap();
+
+    let (result, elapsed) = t.join().unwrap();
+    assert_eq!(result.unwrap_err().reason.unwrap().to_string(), "stop");
+    assert!(elapsed < Duration::from_secs(5));
+}
+
+#[test]
+fn sleep_runs_to_completion_while_alive() {
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+
+    let start = Instant::now();
+    assert!(w.sleep(Duration::from_millis(100)).is_ok());
+    assert!(start.elapsed() >= Duration::from_millis(100));
+
+    let deadline = Instant::now() + Duration::from_millis(50);
+    assert!(w.sleep_until(deadline).is_ok());
+    assert!(Instant::now() >= deadline);
+}
+
+#[test]
+fn sleep_after_kill_returns_immediately() {
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+    kill.kill().unwrap();
+
+    let start = Instant::now();
+    let err = w.sleep(Duration::from_secs(10)).unwrap_err();
+    assert!(err.reason.is_none());
+    assert!(w.sleep_until(start + Duration::from_secs(10)).is_err());
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
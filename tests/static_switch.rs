@@ -0,0 +1,59 @@
+use killswitch_std::{KillSwitch, KillSwitchWatcher, StaticKillSwitch};
+use std::time::Duration;
+
+#[test]
+#[cfg(feature = "std")]
+fn static_shutdown_flag() {
+    use std::thread;
+
+    static SHUTDOWN: StaticKillSwitch = StaticKillSwitch::new();
+
+    assert!(SHUTDOWN.is_alive());
+    assert_eq!(SHUTDOWN.to_string(), "alive");
+
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let w = SHUTDOWN.watcher();
+            thread::spawn(move || w.wait_timeout(Duration::from_secs(5)))
+        })
+        .collect();
+
+    SHUTDOWN.kill_with("shutting down").unwrap();
+    assert!(SHUTDOWN.kill().is_err());
+    assert_eq!(SHUTDOWN.to_string(), "killed");
+
+    for w in workers {
+        assert!(w.join().unwrap());
+    }
+    assert_eq!(
+        SHUTDOWN.watcher().reason().unwrap().to_string(),
+        "shutting down"
+    );
+}
+
+#[tokio::test]
+async fn static_killed_future() {
+    static SHUTDOWN: StaticKillSwitch = StaticKillSwitch::new();
+
+    let handle = tokio::spawn(SHUTDOWN.killed());
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    SHUTDOWN.kill().unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), handle)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[test]
+fn static_watchers_combine_with_heap_switches() {
+    static SHUTDOWN: StaticKillSwitch = StaticKillSwitch::new();
+
+    let task = KillSwitch::default();
+    let either = KillSwitchWatcher::any_of(&[SHUTDOWN.watcher(), task.watcher()]);
+    assert!(either.is_alive());
+
+    SHUTDOWN.kill().unwrap();
+    assert!(!either.is_alive());
+    assert!(task.is_alive());
+}
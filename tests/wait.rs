@@ -0,0 +1,69 @@
+#![cfg(feature = "std")]
+
+use killswitch_std::KillSwitch;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn wait_wakes_on_kill() {
+    let kill = KillSwitch::default();
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let w = kill.watcher();
+            thread::spawn(move || {
+                w.wait();
+                assert!(!w.is_alive());
+            })
+        })
+        .collect();
+
+    thread::sleep(Duration::from_millis(100));
+    kill.kill().unwrap();
+
+    for h in handles {
+        h.join().unwrap();
+    }
+}
+
+#[test]
+fn wait_returns_immediately_when_killed() {
+    let kill = KillSwitch::default();
+    kill.kill().unwrap();
+
+    let start = Instant::now();
+    kill.wait();
+    kill.watcher().wait();
+    assert!(kill.wait_timeout(Duration::from_secs(10)));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn wait_timeout_expires() {
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+
+    let start = Instant::now();
+    assert!(!w.wait_timeout(Duration::from_millis(100)));
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    assert!(w.is_alive());
+}
+
+#[test]
+fn wait_timeout_wakes_on_kill() {
+    let kill = KillSwitch::default();
+    let w = kill.watcher();
+
+    let t = thread::spawn(move || {
+        let start = Instant::now();
+        assert!(w.wait_timeout(Duration::from_secs(10)));
+        start.elapsed()
+    });
+
+    thread::sleep(Duration::from_millis(100));
+    kill.kill().unwrap();
+
+    assert!(t.join().unwrap() < Duration::from_secs(5));
+}
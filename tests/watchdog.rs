@@ -0,0 +1,87 @@
+#![cfg(feature = "std")]
+
+use killswitch_std::{MissedFeed, Watchdog};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn regular_feeds_keep_alive() {
+    let dog = Watchdog::new(Duration::from_millis(100));
+    let feeder = dog.feeder("worker");
+
+    for _ in 0..10 {
+        thread::sleep(Duration::from_millis(20));
+        feeder.feed();
+    }
+    assert!(dog.watcher().is_alive());
+    assert!(dog.last_feed("worker").unwrap() >= Instant::now() - Duration::from_millis(100));
+}
+
+#[test]
+fn quiet_feeder_kills() {
+    let dog = Watchdog::new(Duration::from_millis(100));
+    let w = dog.watcher();
+    let busy = dog.feeder("busy");
+    let stuck = dog.feeder("stuck");
+    let registered = stuck.last_feed();
+
+    let start = Instant::now();
+    while w.is_alive() && start.elapsed() < Duration::from_secs(5) {
+        thread::sleep(Duration::from_millis(10));
+        busy.feed();
+    }
+
+    assert!(!w.is_alive());
+    assert!(start.elapsed() >= Duration::from_millis(90));
+    let reason = w.reason().unwrap();
+    assert_eq!(
+        reason.downcast_ref::<MissedFeed>(),
+        Some(&MissedFeed {
+            feeder: "stuck".into(),
+            last_feed: registered,
+        })
+    );
+}
+
+#[test]
+fn dropped_feeder_is_not_watched() {
+    let dog = Watchdog::new(Duration::from_millis(50));
+    let feeder = dog.feeder("finished");
+    assert_eq!(dog.last_feeds().len(), 1);
+    drop(feeder);
+    assert!(dog.last_feeds().is_empty());
+    assert!(dog.last_feed("finished").is_none());
+
+    thread::sleep(Duration::from_millis(150));
+    assert!(dog.watcher().is_alive());
+
+    // Registering a new feeder restarts the checks
+    let _feeder = dog.feeder("late");
+    assert!(dog.watcher().wait_timeout(Duration::from_secs(5)));
+}
+
+#[test]
+fn last_feed_per_feeder() {
+    let dog = Watchdog::new(Duration::from_secs(60));
+    let a = dog.feeder("a");
+    let b = dog.feeder("b");
+
+    thread::sleep(Duration::from_millis(20));
+    b.feed();
+
+    assert!(dog.last_feed("b").unwrap() > dog.last_feed("a").unwrap());
+    assert_eq!(a.last_feed(), dog.last_feed("a").unwrap());
+    assert_eq!(b.name(), "b");
+    assert!(dog.last_feed("c").is_none());
+}
+
+#[test]
+fn endless_interval_never_expires() {
+    let dog = Watchdog::new(Duration::MAX);
+    let feeder = dog.feeder("x");
+    feeder.feed();
+    thread::sleep(Duration::from_millis(50));
+    assert!(dog.watcher().is_alive());
+}